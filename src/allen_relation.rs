@@ -0,0 +1,197 @@
+use core::cmp::Ordering;
+use core::cmp::PartialOrd;
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+/// The 13 relations of Allen's interval algebra, describing how one interval lies relative to
+/// another. Every pair of intervals is in exactly one of these relations. Bounds are treated as
+/// closed, so intervals that share an endpoint "meet" rather than being "before" each other.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{AllenRelation, BaseInterval};
+///
+/// let x = BaseInterval::new(0, 2);
+/// let y = BaseInterval::new(2, 5);
+/// assert_eq!(x.relation(&y), AllenRelation::Meets);
+/// assert_eq!(y.relation(&x), AllenRelation::MetBy);
+/// assert_eq!(x.relation(&y).inverse(), y.relation(&x));
+/// ```
+pub enum AllenRelation {
+    /// Ends strictly before the other starts
+    Before,
+    /// Ends exactly where the other starts
+    Meets,
+    /// Starts before the other and ends inside it
+    Overlaps,
+    /// Same start, but ends before the other does
+    Starts,
+    /// Strictly inside the other
+    During,
+    /// Same end, but starts after the other does
+    Finishes,
+    /// Same start and end
+    Equals,
+    /// Same end, but starts before the other does
+    FinishedBy,
+    /// Strictly contains the other
+    Contains,
+    /// Same start, but ends after the other does
+    StartedBy,
+    /// Starts inside the other and ends after it
+    OverlappedBy,
+    /// Starts exactly where the other ends
+    MetBy,
+    /// Starts strictly after the other ends
+    After,
+}
+
+impl AllenRelation {
+    /// The relation of `other` to `self`, given that this is the relation of `self` to `other`.
+    pub fn inverse(self) -> AllenRelation {
+        match self {
+            AllenRelation::Before => AllenRelation::After,
+            AllenRelation::Meets => AllenRelation::MetBy,
+            AllenRelation::Overlaps => AllenRelation::OverlappedBy,
+            AllenRelation::Starts => AllenRelation::StartedBy,
+            AllenRelation::During => AllenRelation::Contains,
+            AllenRelation::Finishes => AllenRelation::FinishedBy,
+            AllenRelation::Equals => AllenRelation::Equals,
+            AllenRelation::FinishedBy => AllenRelation::Finishes,
+            AllenRelation::Contains => AllenRelation::During,
+            AllenRelation::StartedBy => AllenRelation::Starts,
+            AllenRelation::OverlappedBy => AllenRelation::Overlaps,
+            AllenRelation::MetBy => AllenRelation::Meets,
+            AllenRelation::After => AllenRelation::Before,
+        }
+    }
+
+    /// Whether the two intervals share at least one point.
+    pub fn is_overlapping(self) -> bool {
+        !matches!(self, AllenRelation::Before | AllenRelation::After)
+    }
+
+    /// Whether the first interval covers all of the second one.
+    pub fn is_superset(self) -> bool {
+        matches!(
+            self,
+            AllenRelation::Equals
+                | AllenRelation::Contains
+                | AllenRelation::StartedBy
+                | AllenRelation::FinishedBy
+        )
+    }
+
+    /// Whether the second interval covers all of the first one.
+    pub fn is_subset(self) -> bool {
+        self.inverse().is_superset()
+    }
+
+    /// Whether the first interval overlaps the second one from the left: it starts no later and
+    /// ends no later, while still reaching the start of the second one.
+    pub fn is_left_overlapping(self) -> bool {
+        matches!(
+            self,
+            AllenRelation::Meets
+                | AllenRelation::Overlaps
+                | AllenRelation::Starts
+                | AllenRelation::FinishedBy
+                | AllenRelation::Equals
+        )
+    }
+
+    /// Whether the first interval overlaps the second one from the right.
+    pub fn is_right_overlapping(self) -> bool {
+        self.inverse().is_left_overlapping()
+    }
+
+    /// Whether the two intervals touch on exactly one endpoint, without any further overlap.
+    pub fn is_touching(self) -> bool {
+        matches!(self, AllenRelation::Meets | AllenRelation::MetBy)
+    }
+}
+
+/// Relation of `[lb, ub]` to `[other_lb, other_ub]`, with both intervals assumed to be ordered.
+pub(crate) fn relation_between<T: PartialOrd>(
+    lb: &T,
+    ub: &T,
+    other_lb: &T,
+    other_ub: &T,
+) -> AllenRelation {
+    match (
+        lb.partial_cmp(other_lb).unwrap(),
+        ub.partial_cmp(other_ub).unwrap(),
+    ) {
+        (Ordering::Equal, Ordering::Equal) => AllenRelation::Equals,
+        (Ordering::Equal, Ordering::Less) => AllenRelation::Starts,
+        (Ordering::Equal, Ordering::Greater) => AllenRelation::StartedBy,
+        (Ordering::Greater, Ordering::Equal) => AllenRelation::Finishes,
+        (Ordering::Less, Ordering::Equal) => AllenRelation::FinishedBy,
+        (Ordering::Greater, Ordering::Less) => AllenRelation::During,
+        (Ordering::Less, Ordering::Greater) => AllenRelation::Contains,
+        (Ordering::Less, Ordering::Less) => match ub.partial_cmp(other_lb).unwrap() {
+            Ordering::Less => AllenRelation::Before,
+            Ordering::Equal => AllenRelation::Meets,
+            Ordering::Greater => AllenRelation::Overlaps,
+        },
+        (Ordering::Greater, Ordering::Greater) => match lb.partial_cmp(other_ub).unwrap() {
+            Ordering::Greater => AllenRelation::After,
+            Ordering::Equal => AllenRelation::MetBy,
+            Ordering::Less => AllenRelation::OverlappedBy,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relation(a: [i64; 2], b: [i64; 2]) -> AllenRelation {
+        relation_between(&a[0], &a[1], &b[0], &b[1])
+    }
+
+    #[test]
+    fn test_all_relations() {
+        assert_eq!(relation([0, 1], [2, 3]), AllenRelation::Before);
+        assert_eq!(relation([0, 2], [2, 3]), AllenRelation::Meets);
+        assert_eq!(relation([0, 2], [1, 3]), AllenRelation::Overlaps);
+        assert_eq!(relation([0, 2], [0, 3]), AllenRelation::Starts);
+        assert_eq!(relation([1, 2], [0, 3]), AllenRelation::During);
+        assert_eq!(relation([1, 3], [0, 3]), AllenRelation::Finishes);
+        assert_eq!(relation([0, 3], [0, 3]), AllenRelation::Equals);
+        assert_eq!(relation([0, 3], [1, 3]), AllenRelation::FinishedBy);
+        assert_eq!(relation([0, 3], [1, 2]), AllenRelation::Contains);
+        assert_eq!(relation([0, 3], [0, 2]), AllenRelation::StartedBy);
+        assert_eq!(relation([1, 3], [0, 2]), AllenRelation::OverlappedBy);
+        assert_eq!(relation([2, 3], [0, 2]), AllenRelation::MetBy);
+        assert_eq!(relation([2, 3], [0, 1]), AllenRelation::After);
+    }
+
+    #[test]
+    fn test_inverse() {
+        for a in 0..4 {
+            for b in a..4 {
+                for c in 0..4 {
+                    for d in c..4 {
+                        let this = relation([a, b], [c, d]);
+                        assert_eq!(this.inverse(), relation([c, d], [a, b]));
+                        assert_eq!(this.inverse().inverse(), this);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_predicates() {
+        assert!(AllenRelation::Meets.is_overlapping());
+        assert!(!AllenRelation::Before.is_overlapping());
+        assert!(AllenRelation::Contains.is_superset());
+        assert!(AllenRelation::During.is_subset());
+        assert!(AllenRelation::Equals.is_subset() && AllenRelation::Equals.is_superset());
+        assert!(AllenRelation::FinishedBy.is_left_overlapping());
+        assert!(AllenRelation::Finishes.is_right_overlapping());
+        assert!(!AllenRelation::Contains.is_left_overlapping());
+        assert!(AllenRelation::MetBy.is_touching());
+    }
+}
@@ -1,9 +1,10 @@
-use intfloat::IntFloat;
+use crate::{AllenRelation, Bound, IntervalLike, ValuedIntervalLike};
+use alloc::format;
+use alloc::string::String;
+use core::cmp::PartialOrd;
+use core::fmt;
+use core::fmt::{Debug, Display, Formatter};
 use num_traits::{Num, ToPrimitive};
-use rust_decimal::Decimal;
-use std::cmp::PartialOrd;
-use std::fmt;
-use std::fmt::{Debug, Display, Formatter};
 
 #[derive(Clone, Copy, Hash, Eq, PartialEq)]
 /// Interval struct that contains a lowerbound and upperbound without a value or count.
@@ -19,7 +20,7 @@ use std::fmt::{Debug, Display, Formatter};
 /// assert_eq!(x.get_ub(), 1);
 /// assert_eq!(x, y.to_base());
 /// ```
-pub struct BaseInterval<T: Num> {
+pub struct BaseInterval<T> {
     lb: T,
     ub: T,
 }
@@ -38,7 +39,7 @@ where
 
 impl<T> Debug for BaseInterval<T>
 where
-    T: Num + PartialOrd + Clone + Display,
+    T: PartialOrd + Clone + Display,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.print())
@@ -47,7 +48,7 @@ where
 
 impl<T> Display for BaseInterval<T>
 where
-    T: Num + PartialOrd + Clone + Display,
+    T: PartialOrd + Clone + Display,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.print())
@@ -56,7 +57,16 @@ where
 
 impl<T> BaseInterval<T>
 where
-    T: Num + PartialOrd + Clone + Display,
+    T: PartialOrd + Clone + Display,
+{
+    pub fn print(&self) -> String {
+        format!("[{};{}]", self.lb, self.ub)
+    }
+}
+
+impl<T> BaseInterval<T>
+where
+    T: PartialOrd + Clone,
 {
     pub fn new(lb: T, ub: T) -> Self {
         if ub > lb {
@@ -66,82 +76,56 @@ where
         }
     }
 
-    pub fn print(&self) -> String {
-        format!("[{};{}]", self.lb, self.ub)
-    }
-
     pub fn to_tuple(self) -> (T, T) {
         (self.lb, self.ub)
     }
 
-    pub fn get_bounds(self) -> (T, T) {
-        (self.lb, self.ub)
-    }
-
-    pub fn get_lb(self) -> T {
-        self.lb
-    }
+    // The methods below are the same as those of IntervalLike, so they can be used without
+    // importing the trait
 
-    pub fn get_ub(self) -> T {
-        self.ub
+    pub fn get_bounds(&self) -> (T, T) {
+        IntervalLike::get_bounds(self)
     }
 
-    pub fn get_width(self) -> T {
-        self.ub - self.lb
+    pub fn get_lb(&self) -> T {
+        IntervalLike::get_lb(self)
     }
 
-    pub fn get_value(self) -> T {
-        // For consistency
-        T::one()
+    pub fn get_ub(&self) -> T {
+        IntervalLike::get_ub(self)
     }
 
-    pub fn len(self) -> T {
-        self.ub - self.lb
+    pub fn contains(&self, num: &T) -> bool {
+        IntervalLike::contains(self, num)
     }
 
-    pub fn contains(self, num: T) -> bool {
-        if (num >= self.lb) & (num <= self.ub) {
-            true
-        } else {
-            false
-        }
+    pub fn relation<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> AllenRelation {
+        IntervalLike::relation(self, other)
     }
 
-    pub fn superset(self, other: BaseInterval<T>) -> bool {
-        if (other.ub <= self.ub) && (other.lb >= self.lb) {
-            true
-        } else {
-            false
-        }
+    pub fn superset<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        IntervalLike::superset(self, other)
     }
 
-    pub fn subset(self, other: BaseInterval<T>) -> bool {
-        other.superset(self)
+    pub fn subset<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        IntervalLike::subset(self, other)
     }
 
-    pub fn left_overlaps(&self, other: &BaseInterval<T>) -> bool {
-        if (self.lb <= other.lb) & (self.ub <= other.ub) & (other.lb <= self.ub) {
-            true
-        } else {
-            false
-        }
+    pub fn left_overlaps<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        IntervalLike::left_overlaps(self, other)
     }
 
-    pub fn right_overlaps(self, other: &BaseInterval<T>) -> bool {
-        other.left_overlaps(&self)
+    pub fn right_overlaps<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        IntervalLike::right_overlaps(self, other)
     }
 
-    pub fn overlaps(self, other: BaseInterval<T>) -> bool {
-        self.left_overlaps(&other) || self.right_overlaps(&other)
+    pub fn overlaps<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        IntervalLike::overlaps(self, other)
     }
 
     pub fn can_join(self, other: BaseInterval<T>) -> bool {
-        // TODO: to test this more
-        if self.overlaps(other) {
-            true
-        } else {
-            false
-        }
+        // Anything that shares at least a border can be joined into one BaseInterval
+        self.overlaps(&other)
     }
 
     pub fn join(self, other: BaseInterval<T>) -> BaseInterval<T> {
@@ -149,38 +133,109 @@ where
             return BaseInterval::new(self.lb, self.ub);
         }
 
-        // Option 2 from above
-        let (lb, ub) = if self.lb < other.lb {
-            (self.lb, other.ub)
+        let lb = if self.lb < other.lb {
+            self.lb
+        } else {
+            other.lb
+        };
+        let ub = if self.ub > other.ub {
+            self.ub
         } else {
-            (other.lb, self.ub)
+            other.ub
         };
         BaseInterval::new(lb, ub)
     }
+}
 
-    pub fn get_total_value(self) -> T {
+impl<T> BaseInterval<T>
+where
+    T: Bound,
+{
+    pub fn get_width(&self) -> T::Width {
+        IntervalLike::get_width(self)
+    }
+
+    pub fn len(self) -> T::Width {
+        T::width(&self.lb, &self.ub)
+    }
+
+    pub fn get_total_value(self) -> T::Width {
         // For consistency
         self.get_width()
     }
+
+    /// The same interval, moved by `delta`.
+    pub fn shift(self, delta: T::Width) -> Self {
+        BaseInterval::new(self.lb.offset(&delta), self.ub.offset(&delta))
+    }
+}
+
+impl<T> BaseInterval<T>
+where
+    T: Num + PartialOrd + Clone,
+{
+    /// The interval with both bounds multiplied by `factor`. A negative factor also mirrors the
+    /// interval around zero, so its bounds swap places.
+    pub fn scale(self, factor: T) -> Self {
+        BaseInterval::new(self.lb * factor.clone(), self.ub * factor)
+    }
+
+    /// The interval mirrored around zero: [lb, ub] becomes [-ub, -lb].
+    pub fn reflect(self) -> Self {
+        BaseInterval::new(T::zero() - self.ub, T::zero() - self.lb)
+    }
+}
+
+impl<T> BaseInterval<T>
+where
+    T: Num + PartialOrd + Clone,
+{
+    pub fn get_value(self) -> T {
+        // For consistency
+        T::one()
+    }
 }
 
-impl BaseInterval<IntFloat> {
-    pub fn to_f32(self) -> (f32, f32) {
-        (self.lb.to_f32().unwrap(), self.ub.to_f32().unwrap())
+impl<T> IntervalLike<T> for BaseInterval<T>
+where
+    T: PartialOrd + Clone,
+{
+    fn get_lb(&self) -> T {
+        self.lb.clone()
+    }
+
+    fn get_ub(&self) -> T {
+        self.ub.clone()
     }
 }
 
-impl BaseInterval<Decimal> {
-    pub fn to_f32(self) -> (f32, f32) {
-        (self.lb.to_f32().unwrap(), self.ub.to_f32().unwrap())
+impl<T> ValuedIntervalLike<T, T> for BaseInterval<T>
+where
+    T: Num + PartialOrd + Clone,
+{
+    fn get_value(&self) -> T {
+        T::one()
+    }
+}
+
+impl<T> BaseInterval<T>
+where
+    T: ToPrimitive,
+{
+    /// Bounds as f32, or None if a bound can not be represented as an f32.
+    pub fn to_f32(self) -> Option<(f32, f32)> {
+        Some((self.lb.to_f32()?, self.ub.to_f32()?))
+    }
+
+    /// Bounds as f64, or None if a bound can not be represented as an f64.
+    pub fn to_f64(self) -> Option<(f64, f64)> {
+        Some((self.lb.to_f64()?, self.ub.to_f64()?))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use intfloat::IntFloat;
-    use num_traits::{FromPrimitive, One};
 
     #[test]
     fn test_create_int_interval() {
@@ -197,8 +252,12 @@ mod tests {
         assert_eq!(a.get_total_value(), 3.0)
     }
 
+    #[cfg(feature = "intfloat")]
     #[test]
     fn test_create_intfloat_interval() {
+        use intfloat::IntFloat;
+        use num_traits::One;
+
         let a = BaseInterval::new(IntFloat::one(), IntFloat::from(2.0, 0));
         assert_eq!(a.len(), IntFloat::one());
         assert_eq!(a.get_value(), IntFloat::one());
@@ -225,10 +284,10 @@ mod tests {
     #[test]
     fn test_contains() {
         let a = BaseInterval::new(3, 7);
-        assert!(a.contains(4));
-        assert!(a.contains(3));
-        assert!(a.contains(7));
-        assert!(!a.contains(0));
+        assert!(a.contains(&4));
+        assert!(a.contains(&3));
+        assert!(a.contains(&7));
+        assert!(!a.contains(&0));
     }
 
     #[test]
@@ -236,10 +295,10 @@ mod tests {
         let a = BaseInterval::new(3, 7);
         let b = BaseInterval::new(4, 6);
 
-        assert!(a.superset(b));
-        assert!(b.subset(a));
-        assert!(!a.subset(b));
-        assert!(!b.superset(a));
+        assert!(a.superset(&b));
+        assert!(b.subset(&a));
+        assert!(!a.subset(&b));
+        assert!(!b.superset(&a));
     }
 
     #[test]
@@ -253,6 +312,17 @@ mod tests {
         assert!(!b.left_overlaps(&a));
     }
 
+    #[test]
+    fn test_overlaps_containment() {
+        let a = BaseInterval::new(0, 10);
+        let b = BaseInterval::new(3, 6);
+
+        assert!(a.overlaps(&b));
+        assert!(b.overlaps(&a));
+        assert!(a.overlaps(&BaseInterval::new(10, 12)));
+        assert!(!a.overlaps(&BaseInterval::new(11, 12)));
+    }
+
     #[test]
     fn test_join() {
         let a = BaseInterval::new(0, 2);
@@ -271,13 +341,80 @@ mod tests {
         assert_eq!(c.join(b), e);
     }
 
+    #[test]
+    fn test_join_containment() {
+        let a = BaseInterval::new(0, 10);
+        let b = BaseInterval::new(3, 6);
+
+        assert_eq!(a.join(b), a);
+        assert_eq!(b.join(a), a);
+    }
+
+    #[test]
+    fn test_relation() {
+        let a = BaseInterval::new(0, 4);
+        assert_eq!(a.relation(&BaseInterval::new(4, 6)), AllenRelation::Meets);
+        assert_eq!(
+            a.relation(&BaseInterval::new(1, 3)),
+            AllenRelation::Contains
+        );
+        assert_eq!(a.relation(&BaseInterval::new(0, 6)), AllenRelation::Starts);
+        assert_eq!(a.relation(&a), AllenRelation::Equals);
+
+        // Predicates agree with the relation, also on touching endpoints and containment
+        let b = BaseInterval::new(4, 6);
+        let c = BaseInterval::new(1, 3);
+        assert!(a.left_overlaps(&b) && a.overlaps(&b) && a.can_join(b));
+        assert!(!a.right_overlaps(&b) && !a.superset(&b));
+        assert!(a.overlaps(&c) && a.superset(&c) && c.subset(&a));
+        assert!(!a.left_overlaps(&c) && !a.right_overlaps(&c));
+        assert_eq!(a.join(c), a);
+        assert!(!c.can_join(b));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_time_bounds() {
+        use std::time::{Duration, Instant};
+
+        let start = Instant::now();
+        let a = BaseInterval::new(start + Duration::from_secs(60), start);
+        assert_eq!(a.get_lb(), start);
+        assert_eq!(a.get_width(), Duration::from_secs(60));
+        assert!(a.contains(&(start + Duration::from_secs(30))));
+        assert!(a.superset(&BaseInterval::new(start, start + Duration::from_secs(10))));
+    }
+
+    #[cfg(all(feature = "decimal", feature = "intfloat"))]
     #[test]
     fn test_to_f32() {
+        use intfloat::IntFloat;
+        use num_traits::FromPrimitive;
+        use rust_decimal::Decimal;
+
         let a = BaseInterval::new(
             Decimal::from_f32(1.2).unwrap(),
             Decimal::from_f32(3.5).unwrap(),
         );
         let b = BaseInterval::new(IntFloat::from(1.2, 1), IntFloat::from(3.5, 1));
         assert_eq!(a.to_f32(), b.to_f32());
+        assert_eq!(b.to_f32(), Some((1.2, 3.5)));
+    }
+
+    #[test]
+    fn test_shift_scale_reflect() {
+        let a = BaseInterval::new(2, 5);
+        assert_eq!(a.shift(3), BaseInterval::new(5, 8));
+        assert_eq!(a.shift(-3), BaseInterval::new(-1, 2));
+        assert_eq!(a.scale(2), BaseInterval::new(4, 10));
+        assert_eq!(a.scale(-2).to_tuple(), (-10, -4));
+        assert_eq!(a.reflect().to_tuple(), (-5, -2));
+    }
+
+    #[test]
+    fn test_to_f64() {
+        assert_eq!(BaseInterval::new(3u8, 7u8).to_f64(), Some((3.0, 7.0)));
+        assert_eq!(BaseInterval::new(0.5f32, 2.0f32).to_f32(), Some((0.5, 2.0)));
+        assert_eq!(BaseInterval::new(-1i128, 1i128).to_f32(), Some((-1.0, 1.0)));
     }
 }
@@ -0,0 +1,588 @@
+use core::cmp::PartialOrd;
+use core::ops::{Add, Sub};
+use core::time::Duration;
+use num_traits::{NumCast, ToPrimitive};
+#[cfg(feature = "std")]
+use std::time::{Instant, SystemTime};
+
+/// Type that can be used as the bound of an interval: something ordered, with a width type that
+/// measures the distance between two bounds. For numbers the width has the same type as the
+/// bounds, but for points in time the width is a duration instead. Bounds and widths behave like
+/// points and distances: the difference of two bounds is a width, and a bound plus a width is a
+/// bound again. This also allows unsigned bounds with signed widths, or addresses with offsets.
+///
+/// Implemented for all standard library numbers, and with the matching features for
+/// `std::time::Instant` and `std::time::SystemTime` (`std`), Decimal (`decimal`), IntFloat
+/// (`intfloat`) and the date and time types of chrono (`chrono`).
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{BaseInterval, Bound};
+///
+/// assert_eq!(BaseInterval::new(3, 7).get_width(), 4);
+/// assert_eq!(Bound::width(&3, &7), 4);
+/// assert_eq!(Bound::offset(&3, &4), 7);
+///
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::time::{Duration, Instant};
+///
+/// let start = Instant::now();
+/// let shift = BaseInterval::new(start, start + Duration::from_secs(8 * 3600));
+/// assert_eq!(shift.get_width(), Duration::from_secs(8 * 3600));
+/// # }
+/// ```
+///
+/// Implementing it for a type of your own, with a width of a different type:
+///
+/// ```
+/// use intervalues::{Bound, Interval};
+///
+/// #[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+/// struct Address(u64);
+///
+/// impl Bound for Address {
+///     type Width = i64;
+///
+///     fn width(lb: &Self, ub: &Self) -> i64 {
+///         ub.0 as i64 - lb.0 as i64
+///     }
+///
+///     fn offset(&self, width: &i64) -> Self {
+///         Address(self.0.checked_add_signed(*width).unwrap())
+///     }
+/// }
+///
+/// let block = Interval::new(Address(0x1000), Address(0x1400), 2i64);
+/// assert_eq!(block.get_width(), 0x400);
+/// assert_eq!(block.get_total_value(), 2048);
+/// assert_eq!(Address(0x1400).offset(&-0x400), Address(0x1000));
+/// ```
+pub trait Bound: PartialOrd + Clone {
+    type Width;
+
+    /// Distance from `lb` to `ub`, assuming `lb <= ub`.
+    fn width(lb: &Self, ub: &Self) -> Self::Width;
+
+    /// The bound that lies `width` away from this one.
+    fn offset(&self, width: &Self::Width) -> Self;
+}
+
+/// Marker for bounds that are plain numbers, with a width of the same type as the bound.
+pub trait NumericWidth: Bound<Width = Self> {}
+
+/// Multiplication of a width with the value of an interval, which gives the total value of that
+/// interval (width × value). For numeric widths this converts the width into the value type; for
+/// durations the result is a duration.
+///
+/// Integer widths can be multiplied by integer and float values, float widths by float values,
+/// and with the matching features integer and Decimal widths by Decimal values and IntFloat
+/// widths by IntFloat values. `Duration` can be multiplied by `u32`, `u64`, `i32`, `i64`, `f32`
+/// and `f64` values, and chrono's `TimeDelta` by `i32`, `i64`, `u32` and `u64` values. Like the
+/// multiplication of these types themselves, this panics on overflow, and for `Duration` also on
+/// a negative result.
+pub trait WidthMul<U> {
+    type Output;
+
+    fn width_mul(self, value: U) -> Self::Output;
+}
+
+fn cast<W: ToPrimitive, U: NumCast>(width: W) -> U {
+    U::from(width).expect("width does not fit in the value type")
+}
+
+#[cfg(feature = "decimal")]
+fn convert<W, U: From<W>>(width: W) -> U {
+    U::from(width)
+}
+
+macro_rules! impl_numeric_mul {
+    ($convert:ident: [$($w:ty),*] => $values:tt) => {
+        $(
+            impl_numeric_mul!(@width $convert: $w => $values);
+        )*
+    };
+    (@width $convert:ident: $w:ty => [$($u:ty),*]) => {
+        $(
+            impl WidthMul<$u> for $w {
+                type Output = $u;
+
+                fn width_mul(self, value: $u) -> $u {
+                    $convert::<$w, $u>(self) * value
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric_mul!(
+    cast: [i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize]
+        => [i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64]
+);
+impl_numeric_mul!(cast: [f32, f64] => [f32, f64]);
+
+#[cfg(feature = "decimal")]
+impl_numeric_mul!(
+    convert: [i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, rust_decimal::Decimal]
+        => [rust_decimal::Decimal]
+);
+
+#[cfg(feature = "intfloat")]
+impl WidthMul<intfloat::IntFloat> for intfloat::IntFloat {
+    type Output = intfloat::IntFloat;
+
+    fn width_mul(self, value: intfloat::IntFloat) -> intfloat::IntFloat {
+        self * value
+    }
+}
+
+/// Width that can be the period of a recurrence, such as a number of hours or a `Duration`. This
+/// allows jumping straight to an occurrence far away, instead of adding up the period.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::Period;
+///
+/// assert_eq!(24.periods_in(&100), 4);
+/// assert_eq!(24.periods_in(&-100), 0);
+/// assert_eq!(24.repeat(4), 96);
+/// ```
+pub trait Period: Sized {
+    /// Number of whole periods that fit in `width`, or zero if `width` is negative.
+    fn periods_in(&self, width: &Self) -> usize;
+
+    /// `count` periods after each other. Panics on overflow.
+    fn repeat(&self, count: usize) -> Self;
+
+    /// Whether `rest`, the part of `total` after a whole number of periods, is only left over
+    /// because of rounding. Only floats round, so for other types this is always false.
+    fn is_rounding_error(&self, _rest: &Self, _total: &Self) -> bool {
+        false
+    }
+}
+
+macro_rules! impl_integer_period {
+    ($($t:ty),*) => {
+        $(
+            impl Period for $t {
+                fn periods_in(&self, width: &Self) -> usize {
+                    if *width < <$t>::default() {
+                        0
+                    } else {
+                        (*width / *self).to_usize().unwrap_or(usize::MAX)
+                    }
+                }
+
+                fn repeat(&self, count: usize) -> Self {
+                    self.checked_mul(cast(count))
+                        .expect("overflow when repeating period")
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_period!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_float_period {
+    ($($t:ty),*) => {
+        $(
+            impl Period for $t {
+                fn periods_in(&self, width: &Self) -> usize {
+                    if *width < 0.0 {
+                        0
+                    } else {
+                        (*width / *self).to_usize().unwrap_or(usize::MAX)
+                    }
+                }
+
+                fn repeat(&self, count: usize) -> Self {
+                    *self * count as $t
+                }
+
+                // Repeating the period and adding it to the start round once each
+                fn is_rounding_error(&self, rest: &Self, total: &Self) -> bool {
+                    *rest <= *total * (4.0 * <$t>::EPSILON)
+                }
+            }
+        )*
+    };
+}
+
+impl_float_period!(f32, f64);
+
+#[cfg(feature = "decimal")]
+impl Period for rust_decimal::Decimal {
+    fn periods_in(&self, width: &Self) -> usize {
+        if width.is_sign_negative() {
+            0
+        } else {
+            (*width / *self).to_usize().unwrap_or(usize::MAX)
+        }
+    }
+
+    fn repeat(&self, count: usize) -> Self {
+        self.checked_mul(count.into())
+            .expect("overflow when repeating period")
+    }
+}
+
+#[cfg(feature = "intfloat")]
+impl Period for intfloat::IntFloat {
+    fn periods_in(&self, width: &Self) -> usize {
+        (*width / *self).to_usize().unwrap_or(0)
+    }
+
+    fn repeat(&self, count: usize) -> Self {
+        intfloat::IntFloat::new(cast(count), 0) * *self
+    }
+}
+
+macro_rules! impl_numeric_bound {
+    ($($t:ty),*) => {
+        $(
+            impl Bound for $t {
+                type Width = $t;
+
+                fn width(lb: &Self, ub: &Self) -> Self::Width {
+                    ub.clone().sub(lb.clone())
+                }
+
+                fn offset(&self, width: &Self::Width) -> Self {
+                    self.clone().add(width.clone())
+                }
+            }
+
+            impl NumericWidth for $t {}
+        )*
+    };
+}
+
+impl_numeric_bound!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+#[cfg(feature = "decimal")]
+impl_numeric_bound!(rust_decimal::Decimal);
+
+#[cfg(feature = "intfloat")]
+impl_numeric_bound!(intfloat::IntFloat);
+
+#[cfg(feature = "std")]
+impl Bound for Instant {
+    type Width = Duration;
+
+    fn width(lb: &Self, ub: &Self) -> Self::Width {
+        ub.duration_since(*lb)
+    }
+
+    fn offset(&self, width: &Self::Width) -> Self {
+        *self + *width
+    }
+}
+
+#[cfg(feature = "std")]
+impl Bound for SystemTime {
+    type Width = Duration;
+
+    fn width(lb: &Self, ub: &Self) -> Self::Width {
+        ub.duration_since(*lb).unwrap_or_default()
+    }
+
+    fn offset(&self, width: &Self::Width) -> Self {
+        *self + *width
+    }
+}
+
+impl WidthMul<u32> for Duration {
+    type Output = Duration;
+
+    fn width_mul(self, value: u32) -> Duration {
+        self * value
+    }
+}
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+// Multiply through the total number of nanoseconds, or None on overflow or a negative result
+fn checked_duration_mul(duration: Duration, value: i128) -> Option<Duration> {
+    let nanos = i128::try_from(duration.as_nanos())
+        .ok()?
+        .checked_mul(value)?;
+    let secs = u64::try_from(nanos.div_euclid(NANOS_PER_SEC)).ok()?;
+    Some(Duration::new(secs, nanos.rem_euclid(NANOS_PER_SEC) as u32))
+}
+
+macro_rules! impl_duration_mul {
+    ($($t:ty),*) => {
+        $(
+            impl WidthMul<$t> for Duration {
+                type Output = Duration;
+
+                fn width_mul(self, value: $t) -> Duration {
+                    checked_duration_mul(self, value as i128)
+                        .expect("overflow or negative result when multiplying duration")
+                }
+            }
+        )*
+    };
+}
+
+impl_duration_mul!(u64, i32, i64);
+
+impl Period for Duration {
+    fn periods_in(&self, width: &Self) -> usize {
+        usize::try_from(width.as_nanos() / self.as_nanos()).unwrap_or(usize::MAX)
+    }
+
+    fn repeat(&self, count: usize) -> Self {
+        checked_duration_mul(*self, count as i128).expect("overflow when repeating period")
+    }
+}
+
+impl WidthMul<f32> for Duration {
+    type Output = Duration;
+
+    fn width_mul(self, value: f32) -> Duration {
+        self.mul_f32(value)
+    }
+}
+
+impl WidthMul<f64> for Duration {
+    type Output = Duration;
+
+    fn width_mul(self, value: f64) -> Duration {
+        self.mul_f64(value)
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_bounds {
+    use super::{Bound, Period, WidthMul, NANOS_PER_SEC};
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone};
+
+    impl Bound for NaiveDateTime {
+        type Width = TimeDelta;
+
+        fn width(lb: &Self, ub: &Self) -> Self::Width {
+            ub.signed_duration_since(*lb)
+        }
+
+        fn offset(&self, width: &Self::Width) -> Self {
+            *self + *width
+        }
+    }
+
+    impl Bound for NaiveDate {
+        type Width = TimeDelta;
+
+        fn width(lb: &Self, ub: &Self) -> Self::Width {
+            ub.signed_duration_since(*lb)
+        }
+
+        fn offset(&self, width: &Self::Width) -> Self {
+            *self + *width
+        }
+    }
+
+    impl Bound for NaiveTime {
+        type Width = TimeDelta;
+
+        fn width(lb: &Self, ub: &Self) -> Self::Width {
+            ub.signed_duration_since(*lb)
+        }
+
+        fn offset(&self, width: &Self::Width) -> Self {
+            *self + *width
+        }
+    }
+
+    impl<Tz: TimeZone> Bound for DateTime<Tz> {
+        type Width = TimeDelta;
+
+        fn width(lb: &Self, ub: &Self) -> Self::Width {
+            ub.clone().signed_duration_since(lb.clone())
+        }
+
+        fn offset(&self, width: &Self::Width) -> Self {
+            self.clone() + *width
+        }
+    }
+
+    impl WidthMul<i32> for TimeDelta {
+        type Output = TimeDelta;
+
+        fn width_mul(self, value: i32) -> TimeDelta {
+            self * value
+        }
+    }
+
+    fn total_nanos(delta: TimeDelta) -> i128 {
+        i128::from(delta.num_seconds()) * NANOS_PER_SEC + i128::from(delta.subsec_nanos())
+    }
+
+    // Multiply through the total number of nanoseconds, or None on overflow
+    fn checked_time_delta_mul(delta: TimeDelta, value: i128) -> Option<TimeDelta> {
+        let nanos = total_nanos(delta).checked_mul(value)?;
+        let secs = i64::try_from(nanos.div_euclid(NANOS_PER_SEC)).ok()?;
+        TimeDelta::new(secs, nanos.rem_euclid(NANOS_PER_SEC) as u32)
+    }
+
+    macro_rules! impl_time_delta_mul {
+        ($($t:ty),*) => {
+            $(
+                impl WidthMul<$t> for TimeDelta {
+                    type Output = TimeDelta;
+
+                    fn width_mul(self, value: $t) -> TimeDelta {
+                        checked_time_delta_mul(self, value as i128)
+                            .expect("overflow when multiplying time delta")
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_time_delta_mul!(i64, u32, u64);
+
+    impl Period for TimeDelta {
+        fn periods_in(&self, width: &Self) -> usize {
+            let periods = total_nanos(*width).max(0) / total_nanos(*self);
+            usize::try_from(periods).unwrap_or(usize::MAX)
+        }
+
+        fn repeat(&self, count: usize) -> Self {
+            checked_time_delta_mul(*self, count as i128).expect("overflow when repeating period")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numeric_width() {
+        assert_eq!(Bound::width(&3, &7), 4);
+        assert_eq!(Bound::width(&1.5, &4.0), 2.5);
+        assert_eq!(Bound::width(&2u8, &5u8), 3u8);
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_decimal_width() {
+        use num_traits::FromPrimitive;
+        use rust_decimal::Decimal;
+
+        assert_eq!(
+            Bound::width(&Decimal::from(2), &Decimal::from_f32(4.5).unwrap()),
+            Decimal::from_f32(2.5).unwrap()
+        );
+        assert_eq!(4i32.width_mul(Decimal::from(3)), Decimal::from(12));
+    }
+
+    #[test]
+    fn test_offset() {
+        assert_eq!(Bound::offset(&3, &4), 7);
+        assert_eq!(Bound::offset(&3, &-4), -1);
+        assert_eq!(Bound::offset(&1.5, &2.0), 3.5);
+    }
+
+    #[test]
+    fn test_width_mul() {
+        assert_eq!(4i64.width_mul(3i64), 12);
+        assert_eq!(2u8.width_mul(1.5f64), 3.0);
+        assert_eq!(3i64.width_mul(0.5f64), 1.5);
+        assert_eq!(3u32.width_mul(-2i32), -6);
+        assert_eq!(
+            Duration::from_secs(60).width_mul(3u32),
+            Duration::from_secs(180)
+        );
+        assert_eq!(
+            Duration::from_secs(60).width_mul(0.5f64),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            Duration::from_millis(1500).width_mul(3i64),
+            Duration::from_millis(4500)
+        );
+        assert_eq!(
+            Duration::from_secs(60).width_mul(2),
+            Duration::from_secs(120)
+        );
+        assert_eq!(
+            Duration::from_secs(1).width_mul(u64::MAX),
+            Duration::from_secs(u64::MAX)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_width_mul_cast_overflow() {
+        300i32.width_mul(1u8);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_width_mul_negative_duration() {
+        Duration::from_secs(60).width_mul(-1i64);
+    }
+
+    #[test]
+    fn test_period() {
+        assert_eq!(7u8.periods_in(&20), 2);
+        assert_eq!(7i32.periods_in(&-20), 0);
+        assert_eq!(7i32.repeat(3), 21);
+        assert_eq!(0.5f64.periods_in(&1.75), 3);
+        assert_eq!(0.5f64.periods_in(&-1.75), 0);
+        assert_eq!(0.5f64.repeat(3), 1.5);
+        assert!(0.1f64.is_rounding_error(&(1.0 - 0.1f64.repeat(9) - 0.9), &1.0));
+        assert!(!0.3f64.is_rounding_error(&0.1, &1.0));
+        assert!(!3i32.is_rounding_error(&1, &10));
+        let hour = Duration::from_secs(3600);
+        assert_eq!(hour.periods_in(&Duration::from_secs(3 * 3600 - 1)), 2);
+        assert_eq!(hour.repeat(24), Duration::from_secs(24 * 3600));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_period_overflow() {
+        100u8.repeat(3);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_time_width() {
+        let start = Instant::now();
+        let end = start + Duration::from_secs(10);
+        assert_eq!(Bound::width(&start, &end), Duration::from_secs(10));
+        assert_eq!(
+            start.offset(&Duration::from_secs(5)),
+            start + Duration::from_secs(5)
+        );
+
+        let start = SystemTime::UNIX_EPOCH;
+        let end = start + Duration::from_millis(1500);
+        assert_eq!(Bound::width(&start, &end), Duration::from_millis(1500));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_width() {
+        use chrono::{NaiveDate, TimeDelta};
+
+        let lb = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let ub = NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+        assert_eq!(Bound::width(&lb, &ub), TimeDelta::days(7));
+        assert_eq!(TimeDelta::hours(2).width_mul(3), TimeDelta::hours(6));
+        assert_eq!(TimeDelta::hours(2).width_mul(-3i64), TimeDelta::hours(-6));
+        assert_eq!(
+            TimeDelta::milliseconds(-1500).width_mul(2u64),
+            TimeDelta::seconds(-3)
+        );
+        assert_eq!(lb.offset(&TimeDelta::days(7)), ub);
+        assert_eq!(TimeDelta::days(1).periods_in(&TimeDelta::hours(49)), 2);
+        assert_eq!(TimeDelta::days(1).periods_in(&TimeDelta::hours(-49)), 0);
+        assert_eq!(TimeDelta::hours(2).repeat(3), TimeDelta::hours(6));
+    }
+}
@@ -0,0 +1,382 @@
+use crate::{BaseInterval, Bound, BoxInterval, WidthMul};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::PartialOrd;
+use core::fmt;
+use core::fmt::{Debug, Display, Formatter};
+use core::ops::{Add, AddAssign};
+use num_traits::{Num, Zero};
+
+#[derive(Clone, Hash, Eq, PartialEq)]
+/// Collection of disjoint BoxIntervals, as returned by `combine_boxes`. Boxes can share a face,
+/// but never overlap in a part with a positive volume.
+pub struct BoxCollection<T, U, const D: usize> {
+    // Grouped by their range on the first axis: boxes in a group share it, and the groups follow
+    // each other without overlap
+    boxes: Vec<BoxInterval<T, U, D>>,
+}
+
+impl<T, U, const D: usize> Debug for BoxCollection<T, U, D>
+where
+    T: PartialOrd + Clone + Display,
+    U: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxCollection")
+            .field("boxes", &self.boxes)
+            .finish()
+    }
+}
+
+impl<T, U, const D: usize> Default for BoxCollection<T, U, D>
+where
+    T: PartialOrd + Clone + Copy,
+    U: Num + PartialOrd + Clone + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, U, const D: usize> BoxCollection<T, U, D>
+where
+    T: PartialOrd + Clone + Copy,
+    U: Num + PartialOrd + Clone + Copy,
+{
+    pub fn new() -> Self {
+        BoxCollection { boxes: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.boxes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    // Boxes that can contain `point`, found by a binary search on the first axis: only the
+    // groups whose range on that axis contains the point
+    fn candidates<'a>(
+        &'a self,
+        point: &'a [T; D],
+    ) -> impl Iterator<Item = &'a BoxInterval<T, U, D>> {
+        let start = match point.first() {
+            Some(num) => self
+                .boxes
+                .partition_point(|x| x.get_axis(0).get_ub() < *num),
+            None => 0,
+        };
+        self.boxes[start..]
+            .iter()
+            .take_while(move |x| match point.first() {
+                Some(num) => x.get_axis(0).get_lb() <= *num,
+                None => true,
+            })
+    }
+
+    /// Value at `point`, or zero if no box contains it. On a face shared by two boxes, the value
+    /// of the first one is returned.
+    pub fn get_value(&self, point: &[T; D]) -> U {
+        self.candidates(point)
+            .find(|x| x.contains(point))
+            .map_or(U::zero(), |x| x.get_value())
+    }
+
+    pub fn contains_point(&self, point: &[T; D]) -> bool {
+        self.candidates(point).any(|x| x.contains(point))
+    }
+
+    pub fn to_vec(&self) -> Vec<BoxInterval<T, U, D>> {
+        self.boxes.clone()
+    }
+
+    pub fn to_vec_owned(self) -> Vec<BoxInterval<T, U, D>> {
+        self.boxes
+    }
+}
+
+impl<T, U, const D: usize> BoxCollection<T, U, D>
+where
+    T: Bound + Copy,
+    T::Width: Num,
+    U: Num + PartialOrd + Clone + Copy,
+{
+    /// Total volume covered by boxes with a non-zero value.
+    pub fn volume(&self) -> T::Width {
+        // Folded from zero rather than summed, since the Sum of IntFloat starts at one
+        self.boxes
+            .iter()
+            .fold(T::Width::zero(), |acc, x| acc + x.get_volume())
+    }
+
+    /// Sum of volume × value over all boxes.
+    pub fn total_value(&self) -> <T::Width as WidthMul<U>>::Output
+    where
+        T::Width: WidthMul<U>,
+        <T::Width as WidthMul<U>>::Output:
+            Default + Add<Output = <T::Width as WidthMul<U>>::Output>,
+    {
+        self.boxes
+            .iter()
+            .fold(Default::default(), |acc, x| acc + x.get_total_value())
+    }
+}
+
+// Boxes as their axes from some axis onwards, with a value
+type Part<T, U> = (Vec<BaseInterval<T>>, U);
+
+// Range on one axis, with the combined parts inside it on the axes after it
+type Slab<T, U> = (T, T, Vec<Part<T, U>>);
+
+// Combine `boxes` on the axes from `axis` onwards. Sweep over the slabs between consecutive
+// bounds on this axis, and combine the boxes covering each slab on the remaining axes. Slabs with
+// the same result as the slab before them are merged into it.
+fn sweep<T, U, const D: usize>(boxes: &[&BoxInterval<T, U, D>], axis: usize) -> Vec<Part<T, U>>
+where
+    T: PartialOrd + Clone + Copy,
+    U: Num + PartialOrd + AddAssign + Clone + Copy,
+{
+    if axis == D {
+        let mut val = U::zero();
+        for this in boxes.iter() {
+            val += this.get_value();
+        }
+        return if val == U::zero() {
+            Vec::new()
+        } else {
+            vec![(Vec::new(), val)]
+        };
+    }
+
+    let mut coords: Vec<T> = Vec::with_capacity(2 * boxes.len());
+    for this in boxes.iter() {
+        coords.push(this.get_axis(axis).get_lb());
+        coords.push(this.get_axis(axis).get_ub());
+    }
+    coords.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    coords.dedup();
+    let mut sorted = boxes.to_vec();
+    sorted.sort_by(|a, b| {
+        let (a, b) = (a.get_axis(axis).get_lb(), b.get_axis(axis).get_lb());
+        a.partial_cmp(&b).unwrap()
+    });
+
+    let mut out = Vec::new();
+    let mut next = 0;
+    let mut active: Vec<&BoxInterval<T, U, D>> = Vec::new();
+    // Slab that is still being extended, with the combined parts inside it
+    let mut open: Option<Slab<T, U>> = None;
+    for slab in coords.windows(2) {
+        let (lb, ub) = (slab[0], slab[1]);
+        active.retain(|x| x.get_axis(axis).get_ub() > lb);
+        while next < sorted.len() && sorted[next].get_axis(axis).get_lb() <= lb {
+            active.push(sorted[next]);
+            next += 1;
+        }
+        let parts = sweep(&active, axis + 1);
+        match open.as_mut() {
+            Some((_, open_ub, open_parts)) if *open_parts == parts => *open_ub = ub,
+            _ => {
+                if let Some(done) = open.take() {
+                    close_slab(&mut out, done);
+                }
+                if !parts.is_empty() {
+                    open = Some((lb, ub, parts));
+                }
+            }
+        }
+    }
+    if let Some(done) = open {
+        close_slab(&mut out, done);
+    }
+    out
+}
+
+// Add the parts inside the slab from `lb` to `ub` to `out`, with the slab as their first axis
+fn close_slab<T, U>(out: &mut Vec<Part<T, U>>, (lb, ub, parts): Slab<T, U>)
+where
+    T: PartialOrd + Clone + Copy,
+{
+    for (axes, val) in parts.into_iter() {
+        let mut new = Vec::with_capacity(axes.len() + 1);
+        new.push(BaseInterval::new(lb, ub));
+        new.extend(axes);
+        out.push((new, val));
+    }
+}
+
+/// Combine valued boxes into disjoint boxes with the summed value of everything covering them,
+/// the multi-dimensional version of `combine_intervals`. Sweeps along the first axis, and
+/// combines the boxes covering each slab between two consecutive bounds on the remaining axes in
+/// the same way. Neighbouring slabs with equal contents are merged again.
+///
+/// For `n` boxes there are at most `2n` slabs on every axis, so this takes O(n^D log n) time in
+/// the worst case, for boxes that all cross each other. That is also the worst-case size of the
+/// result. Memory use follows the size of the result, rather than a grid of all coordinates.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{BaseInterval, BoxInterval, combine_boxes};
+///
+/// let a = BoxInterval::new([BaseInterval::new(0.0, 2.0), BaseInterval::new(0.0, 2.0)], 1.0);
+/// let b = BoxInterval::new([BaseInterval::new(1.0, 3.0), BaseInterval::new(1.0, 3.0)], 1.0);
+/// let out = combine_boxes(vec![a, b]);
+///
+/// assert_eq!(out.get_value(&[0.5, 0.5]), 1.0);
+/// assert_eq!(out.get_value(&[1.5, 1.5]), 2.0);
+/// assert_eq!(out.get_value(&[0.5, 2.5]), 0.0);
+/// assert_eq!(out.volume(), 7.0);
+/// assert_eq!(out.total_value(), 8.0);
+/// ```
+pub fn combine_boxes<T, U, const D: usize>(
+    raw_boxes: Vec<BoxInterval<T, U, D>>,
+) -> BoxCollection<T, U, D>
+where
+    T: PartialOrd + Clone + Copy,
+    U: Num + PartialOrd + AddAssign + Clone + Copy,
+{
+    let refs: Vec<&BoxInterval<T, U, D>> = raw_boxes.iter().collect();
+    let boxes = sweep(&refs, 0)
+        .into_iter()
+        .map(|(axes, val)| BoxInterval::new(core::array::from_fn(|k| axes[k]), val))
+        .collect();
+    BoxCollection { boxes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: [i64; 2], y: [i64; 2], val: i64) -> BoxInterval<i64, i64, 2> {
+        BoxInterval::new(
+            [BaseInterval::new(x[0], x[1]), BaseInterval::new(y[0], y[1])],
+            val,
+        )
+    }
+
+    #[test]
+    fn test_combine_rectangles() {
+        let out = combine_boxes(vec![rect([0, 4], [0, 2], 1), rect([2, 6], [1, 3], 2)]);
+        assert_eq!(out.get_value(&[1, 1]), 1);
+        assert_eq!(out.get_value(&[3, 1]), 1);
+        assert_eq!(out.get_value(&[3, 2]), 3);
+        assert_eq!(out.get_value(&[5, 2]), 2);
+        assert_eq!(out.get_value(&[1, 3]), 0);
+        assert_eq!(out.volume(), 8 + 8 - 2);
+        assert_eq!(out.total_value(), 8 + 16);
+        assert_eq!(out.len(), 5);
+    }
+
+    #[test]
+    fn test_merge_and_cancel() {
+        // Two halves of the same rectangle become one box again
+        let out = combine_boxes(vec![rect([0, 2], [0, 2], 1), rect([2, 4], [0, 2], 1)]);
+        assert_eq!(out.to_vec(), vec![rect([0, 4], [0, 2], 1)]);
+
+        // Opposite values cancel out completely
+        let out = combine_boxes(vec![rect([0, 2], [0, 2], 1), rect([0, 2], [0, 2], -1)]);
+        assert!(out.is_empty());
+        assert!(combine_boxes::<i64, i64, 2>(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_combine_cuboids() {
+        let a = BoxInterval::new(
+            [
+                BaseInterval::new(0.0, 2.0),
+                BaseInterval::new(0.0, 2.0),
+                BaseInterval::new(0.0, 2.0),
+            ],
+            1.0,
+        );
+        let b = BoxInterval::new(
+            [
+                BaseInterval::new(1.0, 3.0),
+                BaseInterval::new(1.0, 3.0),
+                BaseInterval::new(1.0, 3.0),
+            ],
+            0.5,
+        );
+        let out = combine_boxes(vec![a, b]);
+        assert_eq!(out.get_value(&[0.5, 0.5, 0.5]), 1.0);
+        assert_eq!(out.get_value(&[1.5, 1.5, 1.5]), 1.5);
+        assert_eq!(out.get_value(&[2.5, 2.5, 2.5]), 0.5);
+        assert!(!out.contains_point(&[2.5, 0.5, 0.5]));
+        assert_eq!(out.volume(), 15.0);
+        assert_eq!(out.total_value(), 12.0);
+    }
+
+    #[cfg(feature = "intfloat")]
+    #[test]
+    fn test_intfloat_volume() {
+        use intfloat::IntFloat;
+
+        let side = BaseInterval::new(IntFloat::from(0.0, 0), IntFloat::from(2.0, 0));
+        let out = combine_boxes(vec![BoxInterval::new([side, side], IntFloat::from(1.0, 0))]);
+        assert_eq!(out.volume(), IntFloat::from(4.0, 0));
+        assert_eq!(out.total_value(), IntFloat::from(4.0, 0));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_brute_force() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        // Compare with the summed value of the input boxes in the middle of every unit cell
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut next = |n: i64| rng.gen_range(0..n);
+        for _ in 0..200 {
+            let mut input = Vec::new();
+            for _ in 0..(1 + next(6)) {
+                let axes: [BaseInterval<i64>; 3] = core::array::from_fn(|_| {
+                    let lb = next(6);
+                    BaseInterval::new(lb, lb + 1 + next(3))
+                });
+                input.push(BoxInterval::new(axes, next(5) - 2));
+            }
+            let out = combine_boxes(input.clone());
+            let mut cells = 0;
+            for x in 0..9 {
+                for y in 0..9 {
+                    for z in 0..9 {
+                        let inside = |b: &BoxInterval<i64, i64, 3>| {
+                            let axes = b.get_axes();
+                            [x, y, z]
+                                .iter()
+                                .zip(axes.iter())
+                                .all(|(num, axis)| axis.get_lb() <= *num && *num < axis.get_ub())
+                        };
+                        let expected: i64 = input
+                            .iter()
+                            .filter(|b| inside(b))
+                            .map(|b| b.get_value())
+                            .sum();
+                        let found: Vec<&BoxInterval<i64, i64, 3>> =
+                            out.boxes.iter().filter(|b| inside(b)).collect();
+                        assert!(found.len() <= 1);
+                        assert_eq!(found.first().map_or(0, |b| b.get_value()), expected);
+                        if expected != 0 {
+                            cells += 1;
+                        }
+                    }
+                }
+            }
+            assert_eq!(out.volume(), cells);
+        }
+    }
+
+    #[test]
+    fn test_get_value_on_faces() {
+        // The first box wins on a shared face, also between groups on the first axis
+        let out = combine_boxes(vec![rect([0, 2], [0, 2], 1), rect([2, 4], [0, 4], 2)]);
+        assert_eq!(out.get_value(&[2, 1]), 1);
+        assert_eq!(out.get_value(&[2, 3]), 2);
+        assert_eq!(out.get_value(&[4, 4]), 2);
+        assert_eq!(out.get_value(&[5, 1]), 0);
+        assert!(out.contains_point(&[0, 0]));
+        assert!(!out.contains_point(&[-1, 0]));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{BaseInterval, Bound, WidthMul};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::PartialOrd;
+use core::fmt;
+use core::fmt::{Debug, Display, Formatter};
+use num_traits::{Num, One};
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq)]
+/// Box in D dimensions with a value: a BaseInterval per axis, so a rectangle for D = 2 and a
+/// cuboid for D = 3. Can be combined with `combine_boxes` in the same way as Intervals are
+/// combined with `combine_intervals`.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{BaseInterval, BoxInterval};
+///
+/// let room = BoxInterval::new([BaseInterval::new(0, 4), BaseInterval::new(0, 3)], 2);
+/// assert_eq!(room.get_volume(), 12);
+/// assert_eq!(room.get_total_value(), 24);
+/// assert!(room.contains(&[1, 3]));
+/// assert!(!room.contains(&[5, 1]));
+/// ```
+pub struct BoxInterval<T, U, const D: usize> {
+    axes: [BaseInterval<T>; D],
+    val: U,
+}
+
+impl<T, U, const D: usize> Debug for BoxInterval<T, U, D>
+where
+    T: PartialOrd + Clone + Display,
+    U: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.print())
+    }
+}
+
+impl<T, U, const D: usize> Display for BoxInterval<T, U, D>
+where
+    T: PartialOrd + Clone + Display,
+    U: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.print())
+    }
+}
+
+impl<T, U, const D: usize> BoxInterval<T, U, D>
+where
+    T: PartialOrd + Clone + Display,
+    U: Display,
+{
+    pub fn print(&self) -> String {
+        let axes: Vec<String> = self.axes.iter().map(|x| x.print()).collect();
+        format!("({})x{}", axes.join(","), self.val)
+    }
+}
+
+impl<T, U, const D: usize> BoxInterval<T, U, D>
+where
+    T: PartialOrd + Clone,
+    U: Clone,
+{
+    pub fn new(axes: [BaseInterval<T>; D], val: U) -> Self {
+        BoxInterval { axes, val }
+    }
+
+    pub fn get_axes(&self) -> [BaseInterval<T>; D] {
+        self.axes.clone()
+    }
+
+    pub fn get_axis(&self, axis: usize) -> BaseInterval<T> {
+        self.axes[axis].clone()
+    }
+
+    pub fn get_value(&self) -> U {
+        self.val.clone()
+    }
+
+    pub fn contains(&self, point: &[T; D]) -> bool {
+        self.axes
+            .iter()
+            .zip(point.iter())
+            .all(|(axis, num)| axis.contains(num))
+    }
+
+    /// Whether the boxes share at least one point, which includes only touching on a face.
+    pub fn overlaps(&self, other: &BoxInterval<T, U, D>) -> bool {
+        self.axes
+            .iter()
+            .zip(other.axes.iter())
+            .all(|(this, that)| this.overlaps(that))
+    }
+}
+
+impl<T, U, const D: usize> BoxInterval<T, U, D>
+where
+    T: Bound,
+    T::Width: Num,
+    U: Clone,
+{
+    /// Product of the widths of all axes.
+    pub fn get_volume(&self) -> T::Width {
+        self.axes
+            .iter()
+            .fold(T::Width::one(), |acc, axis| acc * axis.get_width())
+    }
+
+    pub fn get_total_value(&self) -> <T::Width as WidthMul<U>>::Output
+    where
+        T::Width: WidthMul<U>,
+    {
+        self.get_volume().width_mul(self.val.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume() {
+        let this = BoxInterval::new(
+            [
+                BaseInterval::new(0, 2),
+                BaseInterval::new(3, 1),
+                BaseInterval::new(0, 5),
+            ],
+            3,
+        );
+        assert_eq!(this.get_axis(1), BaseInterval::new(1, 3));
+        assert_eq!(this.get_volume(), 20);
+        assert_eq!(this.get_total_value(), 60);
+        assert_eq!(this.print(), "([0;2],[1;3],[0;5])x3");
+    }
+
+    #[test]
+    fn test_contains_overlaps() {
+        let a = BoxInterval::new(
+            [BaseInterval::new(0.0, 2.0), BaseInterval::new(0.0, 2.0)],
+            1,
+        );
+        let b = BoxInterval::new(
+            [BaseInterval::new(2.0, 3.0), BaseInterval::new(1.0, 4.0)],
+            1,
+        );
+        let c = BoxInterval::new(
+            [BaseInterval::new(1.0, 3.0), BaseInterval::new(3.0, 4.0)],
+            1,
+        );
+        assert!(a.contains(&[2.0, 0.5]));
+        assert!(!a.contains(&[2.5, 0.5]));
+        assert!(a.overlaps(&b));
+        assert!(b.overlaps(&c));
+        assert!(!a.overlaps(&c));
+    }
+}
@@ -0,0 +1,347 @@
+use crate::{BaseInterval, Interval, IntervalSet};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::PartialOrd;
+use core::fmt;
+use core::fmt::{Debug, Display, Formatter};
+use num_traits::Num;
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+/// Circular domain from `start` to `end`, where `end` is the same point as `start` again, such as
+/// the hours of a day or the degrees of a circle. The period is `end - start`, so `start` and `end`
+/// should differ.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::CircularDomain;
+///
+/// let day = CircularDomain::new(0, 24);
+/// assert_eq!(day.get_period(), 24);
+/// assert_eq!(day.wrap(26), 2);
+/// assert_eq!(day.wrap(-1), 23);
+/// ```
+pub struct CircularDomain<T> {
+    start: T,
+    end: T,
+}
+
+impl<T> CircularDomain<T>
+where
+    T: Num + PartialOrd + Copy,
+{
+    pub fn new(start: T, end: T) -> Self {
+        if start < end {
+            CircularDomain { start, end }
+        } else {
+            CircularDomain {
+                start: end,
+                end: start,
+            }
+        }
+    }
+
+    pub fn get_start(&self) -> T {
+        self.start
+    }
+
+    pub fn get_end(&self) -> T {
+        self.end
+    }
+
+    pub fn get_period(&self) -> T {
+        self.end - self.start
+    }
+
+    /// Map `num` onto the domain, in `[start, end)`.
+    pub fn wrap(&self, num: T) -> T {
+        let period = self.get_period();
+        let rem = (num - self.start) % period;
+        if rem < T::zero() {
+            rem + period + self.start
+        } else {
+            rem + self.start
+        }
+    }
+
+    // Like wrap, but keeps `end` as it is so intervals can run up to the end of the domain
+    fn wrap_bound(&self, num: T) -> T {
+        if num == self.end {
+            num
+        } else {
+            self.wrap(num)
+        }
+    }
+
+    /// Interval from `lb` going forward to `ub`. If `ub` comes before `lb`, the interval wraps
+    /// around the end of the domain instead of having its bounds swapped. An interval of a full
+    /// period or more covers the whole domain, and becomes `[start, end]`.
+    pub fn interval<U>(&self, lb: T, ub: T, val: U) -> CircularInterval<T, U> {
+        // Wrapping both bounds would make these the same point, with a width of zero
+        if ub - lb >= self.get_period() {
+            return CircularInterval {
+                lb: self.start,
+                ub: self.end,
+                val,
+                domain: *self,
+            };
+        }
+        CircularInterval {
+            lb: self.wrap_bound(lb),
+            ub: self.wrap_bound(ub),
+            val,
+            domain: *self,
+        }
+    }
+
+    /// Points covered by any of the intervals, as a set on `[start, end]`.
+    pub fn union<U>(&self, intervals: &[CircularInterval<T, U>]) -> IntervalSet<T>
+    where
+        U: Num + PartialOrd + Copy,
+    {
+        let pieces: Vec<BaseInterval<T>> = intervals
+            .iter()
+            .flat_map(|x| x.split().into_iter().map(|y| y.to_base()))
+            .collect();
+        IntervalSet::from_vec(pieces)
+    }
+
+    /// Points covered by both sets of intervals, as a set on `[start, end]`.
+    pub fn intersection<U>(
+        &self,
+        this: &[CircularInterval<T, U>],
+        other: &[CircularInterval<T, U>],
+    ) -> IntervalSet<T>
+    where
+        U: Num + PartialOrd + Copy,
+    {
+        self.union(this).intersection(&self.union(other))
+    }
+}
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq)]
+/// Interval with a value on a CircularDomain, created with `CircularDomain::interval`. Unlike
+/// Interval, a lowerbound above the upperbound is kept as it is and means the interval wraps
+/// around, so [22, 2] on a day covers the four hours around midnight.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{CircularDomain, Interval, combine_intervals};
+///
+/// let day = CircularDomain::new(0, 24);
+/// let night = day.interval(22, 2, 1);
+/// let late = day.interval(23, 25, 1);
+///
+/// assert!(night.wraps());
+/// assert_eq!(night.get_width(), 4);
+/// assert!(night.contains(0) && night.contains(23) && !night.contains(12));
+/// assert!(night.overlaps(&late));
+/// assert_eq!(night.split(), vec![Interval::new(22, 24, 1), Interval::new(0, 2, 1)]);
+///
+/// let input = [night, late].iter().flat_map(|x| x.split()).collect();
+/// let out = combine_intervals(input);
+/// assert_eq!(out.get_value(0), 2);
+/// assert_eq!(out.get_value(12), 0);
+/// ```
+pub struct CircularInterval<T, U> {
+    lb: T,
+    ub: T,
+    val: U,
+    domain: CircularDomain<T>,
+}
+
+impl<T, U> Debug for CircularInterval<T, U>
+where
+    T: Display,
+    U: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.print())
+    }
+}
+
+impl<T, U> Display for CircularInterval<T, U>
+where
+    T: Display,
+    U: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.print())
+    }
+}
+
+impl<T, U> CircularInterval<T, U>
+where
+    T: Display,
+    U: Display,
+{
+    pub fn print(&self) -> String {
+        format!("[{};{}]x{}", self.lb, self.ub, self.val)
+    }
+}
+
+impl<T, U> CircularInterval<T, U>
+where
+    T: Num + PartialOrd + Copy,
+    U: Num + PartialOrd + Copy,
+{
+    pub fn get_lb(&self) -> T {
+        self.lb
+    }
+
+    pub fn get_ub(&self) -> T {
+        self.ub
+    }
+
+    pub fn get_value(&self) -> U {
+        self.val
+    }
+
+    pub fn get_domain(&self) -> CircularDomain<T> {
+        self.domain
+    }
+
+    /// Whether the interval runs past the end of the domain and continues at its start.
+    pub fn wraps(&self) -> bool {
+        self.lb > self.ub
+    }
+
+    pub fn get_width(&self) -> T {
+        if self.wraps() {
+            (self.domain.end - self.lb) + (self.ub - self.domain.start)
+        } else {
+            self.ub - self.lb
+        }
+    }
+
+    /// Linear Intervals covering the same points, which can be used as input for
+    /// `combine_intervals`. A wrapping interval is split in a part up to the end of the domain and
+    /// a part from the start of the domain.
+    pub fn split(&self) -> Vec<Interval<T, U>> {
+        if self.wraps() {
+            vec![
+                Interval::new(self.lb, self.domain.end, self.val),
+                Interval::new(self.domain.start, self.ub, self.val),
+            ]
+        } else {
+            vec![Interval::new(self.lb, self.ub, self.val)]
+        }
+    }
+
+    pub fn contains(&self, num: T) -> bool {
+        let num = self.domain.wrap(num);
+        let contains = |num: T| {
+            if self.wraps() {
+                num >= self.lb || num <= self.ub
+            } else {
+                num >= self.lb && num <= self.ub
+            }
+        };
+        // The start of the domain is the same point as its end
+        contains(num) || (num == self.domain.start && contains(self.domain.end))
+    }
+
+    /// Whether both intervals share at least one point, taking wrap-around into account. Two arcs
+    /// overlap exactly when one of them contains the start of the other.
+    pub fn overlaps(&self, other: &CircularInterval<T, U>) -> bool {
+        self.contains(other.lb) || other.contains(self.lb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combine_intervals;
+
+    #[test]
+    fn test_wrap() {
+        let circle = CircularDomain::new(0.0, 360.0);
+        assert_eq!(circle.wrap(370.0), 10.0);
+        assert_eq!(circle.wrap(-90.0), 270.0);
+        assert_eq!(circle.wrap(360.0), 0.0);
+
+        let sector = circle.interval(350.0, 10.0, 1.0);
+        assert!(sector.wraps());
+        assert_eq!(sector.get_width(), 20.0);
+        assert!(sector.contains(0.0));
+        assert!(sector.contains(360.0));
+        assert!(sector.contains(-5.0));
+        assert!(!sector.contains(180.0));
+    }
+
+    #[test]
+    fn test_no_swap() {
+        let day = CircularDomain::new(0, 24);
+        let night = day.interval(22, 2, 1);
+        assert_eq!((night.get_lb(), night.get_ub()), (22, 2));
+        assert!(!night.contains(12));
+        let day_time = day.interval(2, 22, 1);
+        assert!(!day_time.wraps());
+        assert!(day_time.contains(12));
+        assert!(!day.interval(0, 24, 1).wraps());
+        assert!(day.interval(0, 24, 1).contains(24));
+    }
+
+    #[test]
+    fn test_full_period() {
+        let day = CircularDomain::new(0, 24);
+        for (lb, ub) in [(6, 30), (-24, 0), (24, 48), (5, 40)] {
+            let all_day = day.interval(lb, ub, 2);
+            assert_eq!((all_day.get_lb(), all_day.get_ub()), (0, 24));
+            assert_eq!(all_day.get_width(), 24);
+            assert!(all_day.contains(6) && all_day.contains(18) && all_day.contains(24));
+            assert_eq!(all_day.split(), vec![Interval::new(0, 24, 2)]);
+            assert!(all_day.overlaps(&day.interval(12, 13, 1)));
+        }
+
+        // Just short of a full period still wraps, and equal bounds are still a single point
+        let almost = day.interval(6, 29, 1);
+        assert_eq!((almost.get_lb(), almost.get_ub()), (6, 5));
+        assert_eq!(almost.get_width(), 23);
+        assert_eq!(day.interval(30, 30, 1).get_width(), 0);
+
+        let circle = CircularDomain::new(0.0, 360.0);
+        assert_eq!(circle.interval(90.0, 450.0, 1.0).get_width(), 360.0);
+        assert_eq!(
+            circle.union(&[circle.interval(90.0, 450.0, 1.0)]).to_vec(),
+            vec![BaseInterval::new(0.0, 360.0)]
+        );
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let day = CircularDomain::new(0, 24);
+        let night = day.interval(22, 2, 1);
+        assert!(night.overlaps(&day.interval(1, 5, 1)));
+        assert!(night.overlaps(&day.interval(20, 22, 1)));
+        assert!(night.overlaps(&day.interval(23, 1, 1)));
+        assert!(!night.overlaps(&day.interval(3, 21, 1)));
+        assert!(day.interval(20, 24, 1).overlaps(&day.interval(0, 3, 1)));
+    }
+
+    #[test]
+    fn test_combine_and_sets() {
+        let day = CircularDomain::new(0, 24);
+        let shops = vec![day.interval(20, 2, 1), day.interval(23, 4, 1)];
+        let input = shops.iter().flat_map(|x| x.split()).collect();
+        let out = combine_intervals(input);
+        assert_eq!(out.get_value(21), 1);
+        assert_eq!(out.get_value(1), 2);
+        assert_eq!(out.get_value(3), 1);
+        assert_eq!(out.get_value(12), 0);
+
+        let union = day.union(&shops);
+        assert_eq!(
+            union.to_vec(),
+            vec![BaseInterval::new(0, 4), BaseInterval::new(20, 24)]
+        );
+        let intersection = day.intersection(&shops[..1], &shops[1..]);
+        assert_eq!(
+            intersection.to_vec(),
+            vec![BaseInterval::new(0, 2), BaseInterval::new(23, 24)]
+        );
+    }
+}
@@ -1,33 +1,70 @@
 use crate::interval::Interval;
-use crate::{BaseInterval, IntervalCollection};
+use crate::{BaseInterval, IntervalCollection, ValuedIntervalLike};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::fmt::Display;
+use core::hash::Hash;
+use core::ops::{AddAssign, SubAssign};
+#[cfg(feature = "std")]
 use defaultmap::DefaultHashMap;
 use itertools::Itertools;
 use num_traits::{Num, ToPrimitive};
-use std::fmt::Debug;
-use std::fmt::Display;
-use std::hash::Hash;
-use std::ops::{AddAssign, SubAssign};
 
-fn intervals_to_points<T, U>(input: Vec<Interval<T, U>>) -> Vec<(T, U)>
+#[cfg(feature = "std")]
+fn intervals_to_points<T, U, I>(input: Vec<I>) -> Vec<(T, U)>
 where
-    T: Num + PartialOrd + Clone + Eq + Hash + Copy + Display + Debug,
+    T: PartialOrd + Clone + Eq + Hash + Copy,
     U: Num + PartialOrd + Default + AddAssign + SubAssign + Clone + Copy + Display + Debug,
+    I: ValuedIntervalLike<T, U>,
 {
     let mut out: DefaultHashMap<T, U> = DefaultHashMap::new();
     for entry in input.iter() {
-        let this = entry.to_tuple();
-        out[this.0] += this.2;
-        out[this.1] -= this.2;
+        let (lb, ub) = entry.get_bounds();
+        let val = entry.get_value();
+        // Ordered explicitly, since implementors of IntervalLike need not order their bounds
+        let (lb, ub) = if lb <= ub { (lb, ub) } else { (ub, lb) };
+        out[lb] += val;
+        out[ub] -= val;
     }
     let mut out: Vec<(T, U)> = out
         .iter()
         .filter(|x| *x.1 != U::zero())
-        .map(|x| (x.0.to_owned(), x.1.to_owned()))
+        .map(|x| (*x.0, *x.1))
         .collect();
     out.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
     out
 }
 
+// Without std there is no HashMap, so sort all endpoints first and then add up equal points
+#[cfg(not(feature = "std"))]
+fn intervals_to_points<T, U, I>(input: Vec<I>) -> Vec<(T, U)>
+where
+    T: PartialOrd + Clone + Eq + Hash + Copy,
+    U: Num + PartialOrd + Default + AddAssign + SubAssign + Clone + Copy + Display + Debug,
+    I: ValuedIntervalLike<T, U>,
+{
+    let mut points: Vec<(T, U)> = Vec::with_capacity(2 * input.len());
+    for entry in input.iter() {
+        let (lb, ub) = entry.get_bounds();
+        let val = entry.get_value();
+        let (lb, ub) = if lb <= ub { (lb, ub) } else { (ub, lb) };
+        points.push((lb, val));
+        points.push((ub, U::zero() - val));
+    }
+    points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let mut out: Vec<(T, U)> = Vec::new();
+    for (pt, val) in points {
+        match out.last_mut() {
+            Some(last) if last.0 == pt => last.1 += val,
+            _ => out.push((pt, val)),
+        }
+    }
+    out.retain(|x| x.1 != U::zero());
+    out
+}
+
 /// Combine intervals with values to an efficient and reduced collection, taking overlaps and
 /// duplicates into account.
 /// Returns an IntervalCollection struct which can be converted further.
@@ -49,9 +86,9 @@ where
 /// assert_eq!(out.to_vec_as_counter()[0], Interval::default());
 /// assert_eq!(out.to_vec_owned()[1], Interval::new(1, 2, 3));
 /// ```
-pub fn combine_intervals<T, U>(raw_ivs: Vec<Interval<T, U>>) -> IntervalCollection<T, U>
+pub fn combine_intervals<T, U, I>(raw_ivs: Vec<I>) -> IntervalCollection<T, U>
 where
-    T: Num + PartialOrd + Clone + Hash + Copy + Eq + Display + Debug,
+    T: PartialOrd + Clone + Hash + Copy + Eq,
     U: Num
         + PartialOrd
         + Default
@@ -60,9 +97,10 @@ where
         + Clone
         + Copy
         + ToPrimitive
-        + std::iter::Sum
+        + core::iter::Sum
         + Display
         + Debug,
+    I: ValuedIntervalLike<T, U>,
 {
     let endpoints: Vec<(T, U)> = intervals_to_points(raw_ivs);
 
@@ -104,49 +142,111 @@ where
 /// // 'out' = {(0, 1, 1), (2, 3, 2), (1, 2, 3)}
 /// assert_eq!(out[0], BaseInterval::new(0, 3));
 /// ```
-pub fn combine_as_set<T, U>(raw_ivs: Vec<Interval<T, U>>) -> Vec<BaseInterval<T>>
+pub fn combine_as_set<T, U, I>(raw_ivs: Vec<I>) -> Vec<BaseInterval<T>>
 where
-    T: Num + PartialOrd + Clone + Hash + Copy + Eq + Display + Debug,
-    U: Num
-        + PartialOrd
-        + Default
-        + AddAssign
-        + SubAssign
-        + Clone
-        + Copy
-        + ToPrimitive
-        + std::iter::Sum
-        + Display
-        + Debug,
+    T: PartialOrd + Clone,
+    U: Num + PartialOrd + AddAssign + Clone,
+    I: ValuedIntervalLike<T, U>,
 {
-    let endpoints: Vec<(T, U)> = intervals_to_points(raw_ivs);
+    // Sort-based sweep over the endpoints, so bounds only need to be ordered (no Hash or Copy)
+    let mut endpoints: Vec<(T, U)> = Vec::new();
+    for entry in raw_ivs.iter() {
+        let (lb, ub) = entry.get_bounds();
+        let val = entry.get_value();
+        let (lb, ub) = if lb <= ub { (lb, ub) } else { (ub, lb) };
+        endpoints.push((lb, val.clone()));
+        endpoints.push((ub, U::zero() - val));
+    }
+    endpoints.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
 
-    // Convert point counts to cumulative point counts
+    // Open an interval when the cumulative value becomes positive, close it when it no longer is
+    let mut out = Vec::new();
     let mut curr_val = U::zero();
-    let mut new_map = Vec::new();
-    for pt in endpoints {
-        curr_val += pt.1;
-        new_map.push((pt.0, curr_val))
+    let mut start: Option<T> = None;
+    let mut endpoints = endpoints.into_iter().peekable();
+    while let Some((pt, val)) = endpoints.next() {
+        curr_val += val;
+        if endpoints.peek().is_some_and(|x| x.0 == pt) {
+            continue;
+        }
+        match start.take() {
+            None if curr_val > U::zero() => start = Some(pt),
+            Some(lb) if curr_val <= U::zero() => out.push(BaseInterval::new(lb, pt)),
+            other => start = other,
+        }
     }
+    out
+}
 
-    // Convert cumulative point counts to intervals
-    let mut out = Vec::new();
-    for (lb, ub) in new_map.iter().tuple_windows() {
-        if lb.1 > U::zero() {
-            match out.last() {
-                None => {
-                    out.push(BaseInterval::new(lb.0, ub.0));
-                }
-                Some(x) => {
-                    if x.get_ub() == lb.0 {
-                        let new_lb = x.get_lb();
-                        out.pop();
-                        out.push(BaseInterval::new(new_lb, ub.0));
-                    } else {
-                        out.push(BaseInterval::new(lb.0, ub.0));
-                    }
+/// Combine intervals with values like combine_intervals, but also keep track of which input
+/// intervals contribute to each resulting piece. Each input interval carries an id (or any other
+/// orderable payload), and each output interval is returned together with the set of ids of the
+/// inputs covering it. The number of contributing inputs is the length of that set.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeSet;
+/// use intervalues::{Interval, combine_intervals_with_ids};
+///
+/// // Two intervals, from 0 to 2 with count 1 and 1 to 3 with count 2
+/// let input = vec![("a", Interval::new(0, 2, 1)), ("b", Interval::new(1, 3, 2))];
+/// let out = combine_intervals_with_ids(input);
+///
+/// assert_eq!(out[1].0, Interval::new(1, 2, 3));
+/// assert_eq!(out[1].1, BTreeSet::from(["a", "b"]));
+/// assert_eq!(out[2].1.len(), 1);
+/// ```
+pub fn combine_intervals_with_ids<T, U, I>(
+    raw_ivs: Vec<(I, Interval<T, U>)>,
+) -> Vec<(Interval<T, U>, BTreeSet<I>)>
+where
+    T: PartialOrd + Clone + Copy,
+    U: Num + PartialOrd + AddAssign + Clone + Copy,
+    I: Ord + Clone,
+{
+    // Each interval adds its value and id at its lowerbound, and removes it at its upperbound
+    let mut endpoints: Vec<(T, U, I, bool)> = Vec::new();
+    for (id, interval) in raw_ivs.into_iter() {
+        let (lb, ub, val) = interval.to_tuple();
+        endpoints.push((lb, val, id.clone(), true));
+        endpoints.push((ub, U::zero() - val, id, false));
+    }
+    endpoints.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    // Sweep over the points, keeping a cumulative value and a count per active id
+    let mut curr_val = U::zero();
+    let mut active: BTreeMap<I, usize> = BTreeMap::new();
+    let mut out: Vec<(Interval<T, U>, BTreeSet<I>)> = Vec::new();
+    let mut i = 0;
+    while i < endpoints.len() {
+        let pt = endpoints[i].0;
+        while i < endpoints.len() && endpoints[i].0 == pt {
+            let (_, val, id, is_start) = &endpoints[i];
+            curr_val += *val;
+            if *is_start {
+                *active.entry(id.clone()).or_insert(0) += 1;
+            } else if let Some(count) = active.get_mut(id) {
+                *count -= 1;
+                if *count == 0 {
+                    active.remove(id);
                 }
             }
+            i += 1;
+        }
+        if i == endpoints.len() || curr_val == U::zero() {
+            continue;
+        }
+
+        let next = endpoints[i].0;
+        let ids: BTreeSet<I> = active.keys().cloned().collect();
+        match out.last_mut() {
+            Some(last)
+                if last.0.get_ub() == pt && last.0.get_value() == curr_val && last.1 == ids =>
+            {
+                last.0 = Interval::new(last.0.get_lb(), next, curr_val);
+            }
+            _ => out.push((Interval::new(pt, next, curr_val), ids)),
         }
     }
     out
@@ -155,6 +255,9 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::IntervalLike;
+    use alloc::string::ToString;
+    use alloc::vec;
 
     #[test]
     fn test_with_overlap() {
@@ -268,4 +371,168 @@ mod tests {
         let that = combine_intervals(that).to_vec_as_set();
         assert_eq!(this, that);
     }
+
+    #[test]
+    fn test_with_ids() {
+        let input = vec![
+            (1, Interval::new(0, 2, 1)),
+            (2, Interval::new(1, 3, 2)),
+            (3, Interval::new(5, 6, 1)),
+        ];
+        let this = combine_intervals_with_ids(input.clone());
+        let that = vec![
+            (Interval::new(0, 1, 1), BTreeSet::from([1])),
+            (Interval::new(1, 2, 3), BTreeSet::from([1, 2])),
+            (Interval::new(2, 3, 2), BTreeSet::from([2])),
+            (Interval::new(5, 6, 1), BTreeSet::from([3])),
+        ];
+        assert_eq!(this, that);
+
+        // The values agree with combine_intervals
+        let values: Vec<Interval<i64, i64>> = this.into_iter().map(|x| x.0).collect();
+        let input = input.into_iter().map(|x| x.1).collect();
+        assert_eq!(values, combine_intervals(input).to_vec());
+    }
+
+    #[test]
+    fn test_with_ids_cancelling_and_repeated() {
+        // Pieces with a combined value of zero are dropped, just like in combine_intervals
+        let input = vec![
+            ("a", Interval::new(0, 2, 2)),
+            ("b", Interval::new(1, 3, -2)),
+            ("a", Interval::new(2, 4, 2)),
+        ];
+        let this = combine_intervals_with_ids(input);
+        let that = vec![
+            (Interval::new(0, 1, 2), BTreeSet::from(["a"])),
+            (Interval::new(3, 4, 2), BTreeSet::from(["a"])),
+        ];
+        assert_eq!(this, that);
+    }
+
+    #[test]
+    fn test_with_ids_merge() {
+        // Same id continuing with the same value is merged, a different id is not
+        let input = vec![
+            ("a", Interval::new(0, 1, 2)),
+            ("a", Interval::new(1, 2, 2)),
+            ("b", Interval::new(2, 3, 2)),
+        ];
+        let this = combine_intervals_with_ids(input);
+        let that = vec![
+            (Interval::new(0, 2, 2), BTreeSet::from(["a"])),
+            (Interval::new(2, 3, 2), BTreeSet::from(["b"])),
+        ];
+        assert_eq!(this, that);
+    }
+
+    #[test]
+    fn test_combine_interval_like() {
+        struct Booking {
+            from: i64,
+            to: i64,
+            guests: i64,
+        }
+
+        impl IntervalLike<i64> for Booking {
+            fn get_lb(&self) -> i64 {
+                self.from
+            }
+
+            fn get_ub(&self) -> i64 {
+                self.to
+            }
+        }
+
+        impl ValuedIntervalLike<i64, i64> for Booking {
+            fn get_value(&self) -> i64 {
+                self.guests
+            }
+        }
+
+        let this = vec![
+            Booking {
+                from: 0,
+                to: 2,
+                guests: 1,
+            },
+            Booking {
+                from: 3,
+                to: 1,
+                guests: 2,
+            },
+        ];
+        let this = combine_intervals(this);
+        let that: Vec<[i64; 3]> = vec![[0, 1, 1], [1, 2, 3], [2, 3, 2]];
+        let that = that
+            .iter()
+            .map(|x| Interval::new(x[0], x[1], x[2]))
+            .collect();
+        assert_eq!(this, IntervalCollection::from_vec(that));
+
+        let this = combine_as_set(vec![BaseInterval::new(0, 2), BaseInterval::new(1, 3)]);
+        assert_eq!(this, vec![BaseInterval::new(0, 3)]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_time_bounds() {
+        use std::time::{Duration, SystemTime};
+
+        let start = SystemTime::UNIX_EPOCH;
+        let hour = Duration::from_secs(3600);
+        let this = vec![
+            Interval::new(start, start + 2 * hour, 1),
+            Interval::new(start + hour, start + 3 * hour, 2),
+        ];
+        let this = combine_intervals(this).to_vec();
+        assert_eq!(this.len(), 3);
+        assert_eq!(this[1].get_bounds(), (start + hour, start + 2 * hour));
+        assert_eq!(this[1].get_value(), 3);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_bounds() {
+        use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let at = |h: u32| -> NaiveDateTime { day.and_hms_opt(h, 0, 0).unwrap() };
+        let shifts = vec![
+            Interval::new(at(8), at(16), 1),
+            Interval::new(at(12), at(20), 1),
+        ];
+        let this = combine_intervals(shifts);
+        assert_eq!(this.get_value(at(13)), 2);
+        assert_eq!(this.total_value(), TimeDelta::hours(16));
+        assert_eq!(
+            combine_as_set(this.to_vec()),
+            vec![BaseInterval::new(at(8), at(20))]
+        );
+    }
+
+    #[test]
+    fn test_set_non_numeric() {
+        let this = vec![
+            Interval::new("apple".to_string(), "cherry".to_string(), 1),
+            Interval::new("banana".to_string(), "date".to_string(), 1),
+            Interval::new("fig".to_string(), "grape".to_string(), 1),
+        ];
+        let this = combine_as_set(this);
+        let that = vec![
+            BaseInterval::new("apple".to_string(), "date".to_string()),
+            BaseInterval::new("fig".to_string(), "grape".to_string()),
+        ];
+        assert_eq!(this, that);
+
+        // Semantic versions as tuples, with a negative value cutting out a range
+        let this = vec![
+            Interval::new((1, 0, 0), (2, 0, 0), 1),
+            Interval::new((1, 4, 0), (1, 5, 0), -1),
+        ];
+        let this = combine_as_set(this);
+        assert_eq!(this.len(), 2);
+        assert_eq!(this[0].get_bounds(), ((1, 0, 0), (1, 4, 0)));
+        assert_eq!(this[1].get_bounds(), ((1, 5, 0), (2, 0, 0)));
+    }
 }
@@ -0,0 +1,323 @@
+use crate::IntervalCollection;
+use alloc::vec::Vec;
+use core::cmp::PartialOrd;
+use num_traits::{Num, ToPrimitive};
+
+#[derive(Clone, PartialEq, Debug)]
+/// Running integral of an IntervalCollection: for every `x`, the total value (width × value) of
+/// everything below `x`. This is a continuous, piecewise-linear function, which is flat in the
+/// gaps between intervals. Values are given as f64.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{Interval, combine_intervals};
+///
+/// // A job using 2 CPUs from t=0 to t=10, and 4 CPUs from t=10 to t=20
+/// let usage = combine_intervals(vec![Interval::new(0, 20, 2), Interval::new(10, 20, 2)]);
+/// let cumulative = usage.cumulative().unwrap();
+///
+/// assert_eq!(cumulative.get_value(5), 10.0);
+/// assert_eq!(cumulative.get_total(), 60.0);
+/// // When did the job reach 40 CPU-seconds?
+/// assert_eq!(cumulative.inverse(40.0), Some(15.0));
+/// ```
+pub struct Cumulative<T> {
+    pieces: Vec<Piece<T>>,
+    // Whether all values are non-negative, so the function never decreases
+    monotone: bool,
+    // Index of the first piece with a value other than zero, where the function starts to change
+    first: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Piece<T> {
+    lb: T,
+    ub: T,
+    lb_f: f64,
+    ub_f: f64,
+    val: f64,
+    // Running integral at lb
+    cum: f64,
+}
+
+impl<T> Piece<T> {
+    fn cum_ub(&self) -> f64 {
+        self.cum + self.val * (self.ub_f - self.lb_f)
+    }
+
+    // Point inside the piece where the running integral is `y`, assuming it lies in this piece
+    fn solve(&self, y: f64) -> f64 {
+        if self.val == 0.0 {
+            self.lb_f
+        } else {
+            (self.lb_f + (y - self.cum) / self.val).clamp(self.lb_f, self.ub_f)
+        }
+    }
+}
+
+impl<T> Cumulative<T>
+where
+    T: PartialOrd + Clone + Copy + ToPrimitive,
+{
+    /// Running integral of `collection`, or None if a bound or value can not be represented as
+    /// an f64.
+    pub fn new<U>(collection: &IntervalCollection<T, U>) -> Option<Self>
+    where
+        U: Num + PartialOrd + Clone + Copy + ToPrimitive,
+    {
+        Cumulative::from_pieces(collection.to_vec().into_iter().map(|x| x.to_tuple()))
+    }
+
+    // Running integral of pieces given as (lb, ub, value), in order and without overlap
+    pub(crate) fn from_pieces<U, I>(input: I) -> Option<Self>
+    where
+        U: ToPrimitive,
+        I: IntoIterator<Item = (T, T, U)>,
+    {
+        let mut pieces = Vec::new();
+        let mut total = 0.0;
+        let mut monotone = true;
+        for (lb, ub, val) in input {
+            let piece = Piece {
+                lb,
+                ub,
+                lb_f: lb.to_f64()?,
+                ub_f: ub.to_f64()?,
+                val: val.to_f64()?,
+                cum: total,
+            };
+            monotone &= piece.val >= 0.0;
+            total = piece.cum_ub();
+            pieces.push(piece);
+        }
+        let first = pieces.iter().position(|x| x.val != 0.0).unwrap_or(0);
+        Some(Cumulative {
+            pieces,
+            monotone,
+            first,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.pieces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pieces.is_empty()
+    }
+
+    /// Lowest and highest bound of the collection, or None if it is empty.
+    pub fn get_bounds(&self) -> Option<(T, T)> {
+        Some((self.pieces.first()?.lb, self.pieces.last()?.ub))
+    }
+
+    /// Running integral at the end of the collection, which is the same as its total value.
+    pub fn get_total(&self) -> f64 {
+        self.pieces.last().map_or(0.0, |x| x.cum_ub())
+    }
+
+    /// Running integral at `x`: the total value of everything below `x`.
+    pub fn get_value(&self, x: T) -> f64 {
+        let x_f = match x.to_f64() {
+            Some(x_f) => x_f,
+            None => return f64::NAN,
+        };
+        let idx = self.pieces.partition_point(|piece| piece.lb <= x);
+        if idx == 0 {
+            return 0.0;
+        }
+        let piece = &self.pieces[idx - 1];
+        piece.cum + piece.val * (x_f.min(piece.ub_f) - piece.lb_f)
+    }
+
+    // Like get_value, for points that are already an f64
+    pub(crate) fn get_value_f64(&self, x: f64) -> f64 {
+        let idx = self.pieces.partition_point(|piece| piece.lb_f <= x);
+        if idx == 0 {
+            return 0.0;
+        }
+        let piece = &self.pieces[idx - 1];
+        piece.cum + piece.val * (x.min(piece.ub_f) - piece.lb_f)
+    }
+
+    // Slope of the running integral at `x`, which is the value of the piece that contains it. On
+    // a border between two pieces the upper piece is used.
+    pub(crate) fn get_slope(&self, x: T) -> f64 {
+        let idx = self.pieces.partition_point(|piece| piece.lb <= x);
+        match idx.checked_sub(1).map(|i| &self.pieces[i]) {
+            Some(piece) if x < piece.ub => piece.val,
+            _ => 0.0,
+        }
+    }
+
+    // Every piece as (lb, ub, value) in f64
+    pub(crate) fn iter_f64(&self) -> impl Iterator<Item = (f64, f64, f64)> + '_ {
+        self.pieces.iter().map(|x| (x.lb_f, x.ub_f, x.val))
+    }
+
+    /// Corners of the piecewise-linear function, as pairs of a point and the running integral at
+    /// that point. Between two corners the function is linear.
+    pub fn get_points(&self) -> Vec<(f64, f64)> {
+        let mut out: Vec<(f64, f64)> = Vec::with_capacity(2 * self.pieces.len());
+        for piece in self.pieces.iter() {
+            if out.last() != Some(&(piece.lb_f, piece.cum)) {
+                out.push((piece.lb_f, piece.cum));
+            }
+            out.push((piece.ub_f, piece.cum_ub()));
+        }
+        out
+    }
+
+    /// First point at which the running integral reaches `y`, or None if it never does. When all
+    /// values are non-negative this is a binary search; otherwise the pieces are scanned in order.
+    ///
+    /// Where the function is flat, the start of the flat stretch is returned. The exception is
+    /// the stretch of pieces with a value of zero at the start of the collection: the running
+    /// integral is zero everywhere before the collection as well, so `inverse(0.0)` returns the
+    /// lowerbound of the first piece with another value instead, where the integral starts to
+    /// change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intervalues::{Interval, IntervalCollection};
+    ///
+    /// let this = IntervalCollection::from_vec(vec![
+    ///     Interval::new(0, 2, 0),
+    ///     Interval::new(2, 3, 1),
+    ///     Interval::new(3, 5, 0),
+    ///     Interval::new(5, 6, 1),
+    /// ]);
+    /// let cumulative = this.cumulative().unwrap();
+    /// assert_eq!(cumulative.inverse(0.0), Some(2.0));
+    /// assert_eq!(cumulative.inverse(1.0), Some(3.0));
+    /// ```
+    pub fn inverse(&self, y: f64) -> Option<f64> {
+        let pieces = &self.pieces[self.first..];
+        if self.monotone {
+            let idx = pieces.partition_point(|piece| piece.cum_ub() < y);
+            let piece = pieces.get(idx)?;
+            if y < piece.cum {
+                return None;
+            }
+            return Some(piece.solve(y));
+        }
+        pieces
+            .iter()
+            .find(|piece| {
+                let (lo, hi) = (piece.cum.min(piece.cum_ub()), piece.cum.max(piece.cum_ub()));
+                lo <= y && y <= hi
+            })
+            .map(|piece| piece.solve(y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{combine_intervals, Interval};
+    use alloc::vec;
+
+    fn example() -> Cumulative<i64> {
+        let collection = combine_intervals(vec![
+            Interval::new(0, 2, 1),
+            Interval::new(1, 3, 2),
+            Interval::new(5, 6, 4),
+        ]);
+        collection.cumulative().unwrap()
+    }
+
+    #[test]
+    fn test_get_value() {
+        let this = example();
+        assert_eq!(this.len(), 4);
+        assert_eq!(this.get_value(-1), 0.0);
+        assert_eq!(this.get_value(1), 1.0);
+        assert_eq!(this.get_value(2), 4.0);
+        assert_eq!(this.get_value(4), 6.0);
+        assert_eq!(this.get_value(6), 10.0);
+        assert_eq!(this.get_value(100), 10.0);
+        assert_eq!(this.get_total(), 10.0);
+        assert_eq!(
+            this.get_points(),
+            vec![
+                (0.0, 0.0),
+                (1.0, 1.0),
+                (2.0, 4.0),
+                (3.0, 6.0),
+                (5.0, 6.0),
+                (6.0, 10.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inverse() {
+        let this = example();
+        assert_eq!(this.inverse(0.0), Some(0.0));
+        assert_eq!(this.inverse(2.5), Some(1.5));
+        // The function is flat between 3 and 5, so 6 is first reached at 3
+        assert_eq!(this.inverse(6.0), Some(3.0));
+        assert_eq!(this.inverse(8.0), Some(5.5));
+        assert_eq!(this.inverse(10.0), Some(6.0));
+        assert_eq!(this.inverse(10.5), None);
+        assert_eq!(this.inverse(-1.0), None);
+    }
+
+    #[test]
+    fn test_inverse_flat() {
+        // Leading pieces with a value of zero are skipped, later ones give their start
+        let collection = IntervalCollection::from_vec(vec![
+            Interval::new(0, 2, 0),
+            Interval::new(2, 4, 3),
+            Interval::new(4, 7, 0),
+            Interval::new(7, 8, 1),
+            Interval::new(8, 9, 0),
+        ]);
+        let this = collection.cumulative().unwrap();
+        assert_eq!(this.get_bounds(), Some((0, 9)));
+        assert_eq!(this.inverse(0.0), Some(2.0));
+        assert_eq!(this.inverse(6.0), Some(4.0));
+        assert_eq!(this.inverse(7.0), Some(8.0));
+        assert_eq!(this.inverse(7.5), None);
+
+        // With negative values as well
+        let collection = IntervalCollection::from_vec(vec![
+            Interval::new(0.0, 2.0, 0.0),
+            Interval::new(2.0, 4.0, -1.0),
+            Interval::new(4.0, 5.0, 0.0),
+        ]);
+        let this = collection.cumulative().unwrap();
+        assert_eq!(this.inverse(0.0), Some(2.0));
+        assert_eq!(this.inverse(-2.0), Some(4.0));
+
+        // When all values are zero, the first point is all there is
+        let collection = IntervalCollection::from_vec(vec![Interval::new(1, 2, 0)]);
+        assert_eq!(collection.cumulative().unwrap().inverse(0.0), Some(1.0));
+    }
+
+    #[test]
+    fn test_negative_values() {
+        let collection = IntervalCollection::from_vec(vec![
+            Interval::new(0.0, 2.0, 3.0),
+            Interval::new(2.0, 6.0, -2.0),
+        ]);
+        let this = collection.cumulative().unwrap();
+        assert_eq!(this.get_value(2.0), 6.0);
+        assert_eq!(this.get_total(), -2.0);
+        assert_eq!(this.inverse(4.0), Some(4.0 / 3.0));
+        assert_eq!(this.inverse(-1.0), Some(5.5));
+        assert_eq!(this.inverse(7.0), None);
+    }
+
+    #[test]
+    fn test_empty() {
+        let collection: IntervalCollection<i64, i64> = IntervalCollection::new();
+        let this = collection.cumulative().unwrap();
+        assert!(this.is_empty());
+        assert_eq!(this.get_bounds(), None);
+        assert_eq!(this.get_value(3), 0.0);
+        assert_eq!(this.inverse(0.0), None);
+    }
+}
@@ -1,10 +1,12 @@
-use crate::BaseInterval;
-use intfloat::IntFloat;
+use crate::{
+    AllenRelation, BaseInterval, Bound, IntervalLike, ValueScaling, ValuedIntervalLike, WidthMul,
+};
+use alloc::format;
+use alloc::string::String;
+use core::cmp::PartialOrd;
+use core::fmt;
+use core::fmt::{Debug, Display, Formatter};
 use num_traits::{Num, ToPrimitive};
-use rust_decimal::Decimal;
-use std::cmp::PartialOrd;
-use std::fmt;
-use std::fmt::{Debug, Display, Formatter};
 
 #[derive(Clone, Copy, Hash, Eq, PartialEq)]
 /// Interval struct that contains a lowerbound, upperbound and value/count of the range within the
@@ -20,7 +22,7 @@ use std::fmt::{Debug, Display, Formatter};
 /// assert_eq!(x.get_ub(), 1);
 /// assert_eq!(x.get_value(), 2.5);
 /// ```
-pub struct Interval<T: Num, U: Num> {
+pub struct Interval<T, U> {
     lb: T,
     ub: T,
     val: U,
@@ -42,7 +44,7 @@ where
 
 impl<T, U> Debug for Interval<T, U>
 where
-    T: Num + PartialOrd + Clone + Display,
+    T: PartialOrd + Clone + Display,
     U: Num + PartialOrd + Display,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -52,7 +54,7 @@ where
 
 impl<T, U> Display for Interval<T, U>
 where
-    T: Num + PartialOrd + Clone + Display,
+    T: PartialOrd + Clone + Display,
     U: Num + PartialOrd + Display,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -62,8 +64,18 @@ where
 
 impl<T, U> Interval<T, U>
 where
-    T: Num + PartialOrd + Clone + Display,
+    T: PartialOrd + Clone + Display,
     U: Num + PartialOrd + Display,
+{
+    pub fn print(&self) -> String {
+        format!("[{};{}]x{}", self.lb, self.ub, self.val)
+    }
+}
+
+impl<T, U> Interval<T, U>
+where
+    T: PartialOrd + Clone,
+    U: Num + PartialOrd,
 {
     pub fn new(lb: T, ub: T, val: U) -> Self {
         if ub > lb {
@@ -77,82 +89,63 @@ where
         }
     }
 
-    pub fn print(&self) -> String {
-        format!("[{};{}]x{}", self.lb, self.ub, self.val)
-    }
-
     pub fn to_tuple(self) -> (T, T, U) {
         (self.lb, self.ub, self.val)
     }
 
-    pub fn get_bounds(self) -> (T, T) {
-        (self.lb, self.ub)
-    }
+    // The methods below are the same as those of IntervalLike, so they can be used without
+    // importing the trait
 
-    pub fn get_lb(self) -> T {
-        self.lb
+    pub fn get_bounds(&self) -> (T, T) {
+        IntervalLike::get_bounds(self)
     }
 
-    pub fn get_ub(self) -> T {
-        self.ub
+    pub fn get_lb(&self) -> T {
+        IntervalLike::get_lb(self)
     }
 
-    pub fn get_width(self) -> T {
-        self.ub - self.lb
+    pub fn get_ub(&self) -> T {
+        IntervalLike::get_ub(self)
     }
 
     pub fn get_value(self) -> U {
         self.val
     }
 
-    pub fn len(self) -> T {
-        self.ub - self.lb
+    pub fn contains(&self, num: &T) -> bool {
+        IntervalLike::contains(self, num)
     }
 
-    pub fn contains(self, num: T) -> bool {
-        if (num >= self.lb) & (num <= self.ub) {
-            true
-        } else {
-            false
-        }
+    pub fn relation<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> AllenRelation {
+        IntervalLike::relation(self, other)
     }
 
-    // TODO explore if T can be U here
-    pub fn superset(self, other: Interval<T, U>) -> bool {
-        if (other.ub <= self.ub) && (other.lb >= self.lb) {
-            true
-        } else {
-            false
-        }
+    pub fn superset<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        IntervalLike::superset(self, other)
     }
 
-    pub fn subset(self, other: Interval<T, U>) -> bool {
-        other.superset(self)
+    pub fn subset<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        IntervalLike::subset(self, other)
     }
 
-    pub fn left_overlaps(&self, other: &Interval<T, U>) -> bool {
-        if (self.lb <= other.lb) & (self.ub <= other.ub) & (other.lb <= self.ub) {
-            true
-        } else {
-            false
-        }
+    pub fn left_overlaps<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        IntervalLike::left_overlaps(self, other)
     }
 
-    pub fn right_overlaps(self, other: &Interval<T, U>) -> bool {
-        other.left_overlaps(&self)
+    pub fn right_overlaps<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        IntervalLike::right_overlaps(self, other)
     }
 
-    pub fn overlaps(self, other: &Interval<T, U>) -> bool {
-        self.left_overlaps(other) || self.right_overlaps(other)
+    pub fn overlaps<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        IntervalLike::overlaps(self, other)
     }
 
     pub fn can_join(self, other: &Interval<T, U>) -> bool {
-        if ((self.ub == other.lb) || (other.ub == self.lb)) && (self.val == other.val) {
-            true
-        } else if (self.ub == other.ub) && (self.lb == other.lb) {
-            true
-        } else {
-            false
+        // Two options -> same range, or bordering range but same val
+        match self.relation(other) {
+            AllenRelation::Equals => true,
+            AllenRelation::Meets | AllenRelation::MetBy => self.val == other.val,
+            _ => false,
         }
     }
 
@@ -164,20 +157,21 @@ where
         }
 
         // Option 2 from above
-        let (lb, ub) = if self.lb < other.lb {
-            (self.lb, other.ub)
+        let lb = if self.lb < other.lb {
+            self.lb
+        } else {
+            other.lb
+        };
+        let ub = if self.ub > other.ub {
+            self.ub
         } else {
-            (other.lb, self.ub)
+            other.ub
         };
         Interval::new(lb, ub, self.val)
     }
 
     pub fn can_join_as_set(self, other: &Interval<T, U>) -> bool {
-        if self.overlaps(other) {
-            true
-        } else {
-            false
-        }
+        self.overlaps(other)
     }
 
     pub fn join_ign_value(self, other: Interval<T, U>) -> Interval<T, U> {
@@ -208,23 +202,100 @@ where
         BaseInterval::new(lb, ub)
     }
 
-    pub fn to_base(self) -> BaseInterval<T> {
-        BaseInterval::new(self.lb, self.ub)
+    pub fn to_base(&self) -> BaseInterval<T> {
+        IntervalLike::to_base(self)
+    }
+}
+
+impl<T, U> Interval<T, U>
+where
+    T: Bound,
+    U: Num,
+{
+    pub fn get_width(&self) -> T::Width {
+        IntervalLike::get_width(self)
+    }
+
+    pub fn len(self) -> T::Width {
+        T::width(&self.lb, &self.ub)
     }
 }
 
-impl<T> Interval<T, T>
+impl<T, U> Interval<T, U>
 where
-    T: Num,
+    T: Bound,
+    U: Num + PartialOrd,
 {
-    pub fn get_total_value(self) -> T {
-        (self.ub - self.lb) * self.val
+    /// The same interval with the same value, moved by `delta`.
+    pub fn shift(self, delta: T::Width) -> Self {
+        Interval::new(self.lb.offset(&delta), self.ub.offset(&delta), self.val)
     }
 }
 
 impl<T, U> Interval<T, U>
 where
-    T: Num + Clone + PartialOrd + Display,
+    T: Num + PartialOrd + Clone,
+    U: Num + PartialOrd,
+{
+    /// The interval with both bounds multiplied by `factor`, with the value changed as given by
+    /// `values`. A negative factor also mirrors the interval around zero, so its bounds swap
+    /// places. None when scaling by zero with `ValueScaling::Integral`.
+    pub fn scale(self, factor: T, values: ValueScaling) -> Option<Self>
+    where
+        T: WidthMul<U, Output = U>,
+    {
+        let val = values.apply(self.val, factor.clone())?;
+        Some(Interval::new(
+            self.lb * factor.clone(),
+            self.ub * factor,
+            val,
+        ))
+    }
+
+    /// The interval mirrored around zero with the same value: [lb, ub] becomes [-ub, -lb].
+    pub fn reflect(self) -> Self {
+        Interval::new(T::zero() - self.ub, T::zero() - self.lb, self.val)
+    }
+}
+
+impl<T, U> IntervalLike<T> for Interval<T, U>
+where
+    T: PartialOrd + Clone,
+    U: Num,
+{
+    fn get_lb(&self) -> T {
+        self.lb.clone()
+    }
+
+    fn get_ub(&self) -> T {
+        self.ub.clone()
+    }
+}
+
+impl<T, U> ValuedIntervalLike<T, U> for Interval<T, U>
+where
+    T: PartialOrd + Clone,
+    U: Num + Clone,
+{
+    fn get_value(&self) -> U {
+        self.val.clone()
+    }
+}
+
+impl<T, U> Interval<T, U>
+where
+    T: Bound,
+    U: Num,
+    T::Width: WidthMul<U>,
+{
+    pub fn get_total_value(self) -> <T::Width as WidthMul<U>>::Output {
+        T::width(&self.lb, &self.ub).width_mul(self.val)
+    }
+}
+
+impl<T, U> Interval<T, U>
+where
+    T: Clone + PartialOrd,
     U: Num + PartialOrd + ToPrimitive,
 {
     pub fn val_to_count(self) -> Interval<T, usize> {
@@ -237,31 +308,25 @@ where
     }
 }
 
-impl Interval<IntFloat, IntFloat> {
-    pub fn to_f32(self) -> (f32, f32, f32) {
-        (
-            self.lb.to_f32().unwrap(),
-            self.ub.to_f32().unwrap(),
-            self.val.to_f32().unwrap(),
-        )
+impl<T, U> Interval<T, U>
+where
+    T: ToPrimitive,
+    U: ToPrimitive,
+{
+    /// Bounds and value as f32, or None if any of them can not be represented as an f32.
+    pub fn to_f32(self) -> Option<(f32, f32, f32)> {
+        Some((self.lb.to_f32()?, self.ub.to_f32()?, self.val.to_f32()?))
     }
-}
 
-impl Interval<Decimal, Decimal> {
-    pub fn to_f32(self) -> (f32, f32, f32) {
-        (
-            self.lb.to_f32().unwrap(),
-            self.ub.to_f32().unwrap(),
-            self.val.to_f32().unwrap(),
-        )
+    /// Bounds and value as f64, or None if any of them can not be represented as an f64.
+    pub fn to_f64(self) -> Option<(f64, f64, f64)> {
+        Some((self.lb.to_f64()?, self.ub.to_f64()?, self.val.to_f64()?))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use intfloat::IntFloat;
-    use num_traits::{FromPrimitive, One};
 
     #[test]
     fn test_create_int_interval() {
@@ -292,8 +357,12 @@ mod tests {
         assert_eq!(a.get_value(), 1.0)
     }
 
+    #[cfg(feature = "intfloat")]
     #[test]
     fn test_create_intfloat_interval() {
+        use intfloat::IntFloat;
+        use num_traits::One;
+
         let a = Interval::new(
             IntFloat::one(),
             IntFloat::from(3.0, 0),
@@ -319,15 +388,21 @@ mod tests {
         let a = Interval::new(3, 7, 2);
         assert_eq!(a.get_total_value(), 8);
         assert_eq!(a.get_value(), 2);
+
+        // Width and value of different types
+        let b = Interval::new(3u8, 7u8, 2.5);
+        assert_eq!(b.get_total_value(), 10.0);
+        let c = Interval::new(3i32, 7i32, 0.5f64);
+        assert_eq!(c.get_total_value(), 2.0);
     }
 
     #[test]
     fn test_contains() {
         let a = Interval::new(3, 7, 2);
-        assert!(a.contains(4));
-        assert!(a.contains(3));
-        assert!(a.contains(7));
-        assert!(!a.contains(0));
+        assert!(a.contains(&4));
+        assert!(a.contains(&3));
+        assert!(a.contains(&7));
+        assert!(!a.contains(&0));
     }
 
     #[test]
@@ -335,10 +410,10 @@ mod tests {
         let a = Interval::new(3, 7, 2);
         let b = Interval::new(4, 6, 1);
 
-        assert!(a.superset(b));
-        assert!(b.subset(a));
-        assert!(!a.subset(b));
-        assert!(!b.superset(a));
+        assert!(a.superset(&b));
+        assert!(b.subset(&a));
+        assert!(!a.subset(&b));
+        assert!(!b.superset(&a));
     }
 
     #[test]
@@ -352,6 +427,17 @@ mod tests {
         assert!(!b.left_overlaps(&a));
     }
 
+    #[test]
+    fn test_overlaps_containment() {
+        let a = Interval::new(0, 10, 1);
+        let b = Interval::new(3, 6, 2);
+
+        assert!(a.overlaps(&b));
+        assert!(b.overlaps(&a));
+        assert!(a.can_join_as_set(&b));
+        assert!(!a.overlaps(&Interval::new(11, 12, 1)));
+    }
+
     #[test]
     fn test_join() {
         let a = Interval::new(0, 2, 1);
@@ -370,6 +456,15 @@ mod tests {
         assert_eq!(c.join(b), e);
     }
 
+    #[test]
+    fn test_join_containment() {
+        let a = Interval::new(0, 10, 1);
+        let b = Interval::new(3, 6, 1);
+
+        assert_eq!(a.join(b), a);
+        assert_eq!(b.join(a), a);
+    }
+
     #[test]
     fn test_join_ign_value() {
         let a = Interval::new(0, 2, 2);
@@ -393,6 +488,24 @@ mod tests {
         assert_eq!(c.join_ign_value(b), e2);
     }
 
+    #[test]
+    fn test_relation() {
+        let a = Interval::new(0, 4, 1);
+        let b = Interval::new(4, 6, 1);
+        let c = Interval::new(1, 3, 2);
+        assert_eq!(a.relation(&b), AllenRelation::Meets);
+        assert_eq!(b.relation(&a), AllenRelation::MetBy);
+        assert_eq!(a.relation(&c), AllenRelation::Contains);
+        assert_eq!(a.relation(&c), a.to_base().relation(&c.to_base()));
+
+        // Predicates agree with the relation, also on touching endpoints and containment
+        assert!(a.left_overlaps(&b) && a.overlaps(&b) && a.can_join(&b));
+        assert!(a.overlaps(&c) && a.can_join_as_set(&c) && !a.can_join(&c));
+        assert!(a.superset(&c) && c.subset(&a));
+        assert!(!a.left_overlaps(&c) && !a.right_overlaps(&c));
+        assert!(!b.can_join(&Interval::new(6, 8, 2)));
+    }
+
     #[test]
     fn test_val_to_count() {
         let a = Interval::new(0, 2, 3.5);
@@ -411,8 +524,25 @@ mod tests {
         assert_eq!(a.to_base(), b)
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_time_bounds() {
+        use std::time::{Duration, SystemTime};
+
+        let start = SystemTime::UNIX_EPOCH;
+        let a = Interval::new(start, start + Duration::from_secs(3600), 2);
+        assert_eq!(a.get_width(), Duration::from_secs(3600));
+        assert!(a.contains(&(start + Duration::from_secs(60))));
+        assert!(a.can_join(&Interval::new(start + Duration::from_secs(3600), start, 2)));
+    }
+
+    #[cfg(all(feature = "decimal", feature = "intfloat"))]
     #[test]
     fn test_to_f32() {
+        use intfloat::IntFloat;
+        use num_traits::FromPrimitive;
+        use rust_decimal::Decimal;
+
         let a = Interval::new(
             Decimal::from_f32(1.2).unwrap(),
             Decimal::from_f32(3.5).unwrap(),
@@ -424,5 +554,55 @@ mod tests {
             IntFloat::from(2.5, 1),
         );
         assert_eq!(a.to_f32(), b.to_f32());
+        assert_eq!(b.to_f32(), Some((1.2, 3.5, 2.5)));
+    }
+
+    #[test]
+    fn test_shift_scale_reflect() {
+        let a = Interval::new(2, 5, 6);
+        assert_eq!(a.shift(-2), Interval::new(0, 3, 6));
+        assert_eq!(
+            a.scale(2, ValueScaling::Density),
+            Some(Interval::new(4, 10, 6))
+        );
+        assert_eq!(
+            a.scale(-3, ValueScaling::Integral),
+            Some(Interval::new(-15, -6, 2))
+        );
+        assert_eq!(
+            a.scale(-3, ValueScaling::Integral)
+                .unwrap()
+                .get_total_value(),
+            a.get_total_value()
+        );
+        assert_eq!(a.reflect().to_tuple(), (-5, -2, 6));
+
+        // Values of another type than the bounds, converted from the factor
+        let b = Interval::new(1.0f32, 3.0f32, 1.0f64);
+        assert_eq!(
+            b.scale(0.5, ValueScaling::Integral).unwrap().get_value(),
+            2.0
+        );
+        let c = Interval::new(1i64, 3i64, 1.0f64);
+        assert_eq!(
+            c.scale(4, ValueScaling::Integral),
+            Some(Interval::new(4, 12, 0.25))
+        );
+
+        // The total value can not stay the same when scaling to a width of zero
+        assert_eq!(a.scale(0, ValueScaling::Integral), None);
+        assert_eq!(
+            a.scale(0, ValueScaling::Density),
+            Some(Interval::new(0, 0, 6))
+        );
+    }
+
+    #[test]
+    fn test_to_f64() {
+        assert_eq!(Interval::new(3, 7, 2u64).to_f64(), Some((3.0, 7.0, 2.0)));
+        assert_eq!(
+            Interval::new(3i64, 7i64, 0.5f32).to_f32(),
+            Some((3.0, 7.0, 0.5))
+        );
     }
 }
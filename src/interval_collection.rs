@@ -1,10 +1,22 @@
-use crate::{BaseInterval, Interval};
-use num_traits::{Num, ToPrimitive};
-use safecast::CastInto;
-use std::fmt;
-use std::fmt::{Debug, Display, Formatter};
+use crate::{
+    BaseInterval, Bound, Cumulative, Interval, Period, PiecewiseLinear, ResampleMode, ValueScaling,
+    WidthMul,
+};
+#[cfg(feature = "rand")]
+use crate::{IntervalPdf, IntervalPdfError};
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::{Debug, Display, Formatter};
+use core::ops::Add;
+use num_traits::{Num, NumCast, ToPrimitive};
+#[cfg(feature = "rand")]
+use rand::Rng;
 
-#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+#[derive(Clone, Hash, Eq, PartialEq)]
 /// Result of combine_intervals: a collection of Intervals. This can be converted to a Vec of
 /// Intervals, or converted to a Counter (only integer and positive counts instead of values) or
 /// Set (any Interval with value >0 is included and if possible combined with neighbouring
@@ -27,34 +39,47 @@ use std::fmt::{Debug, Display, Formatter};
 /// assert_eq!(out.to_vec()[1], Interval::new(1, 2, 3));
 /// assert_eq!(out.to_vec_as_set(), vec!(BaseInterval::new(0, 3)))
 /// ```
-pub struct IntervalCollection<T: Num + PartialOrd + Clone + Display, U: Num + PartialOrd + Display>
-{
+pub struct IntervalCollection<T, U> {
     intervals: Vec<Interval<T, U>>,
 }
 
+impl<T, U> Debug for IntervalCollection<T, U>
+where
+    T: PartialOrd + Clone + Display,
+    U: Num + PartialOrd + Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IntervalCollection")
+            .field("intervals", &self.intervals)
+            .finish()
+    }
+}
+
 impl<T, U> Display for IntervalCollection<T, U>
 where
-    T: Num + PartialOrd + Clone + Copy + Display,
-    U: Num + PartialOrd + Clone + Copy + Display + std::iter::Sum,
+    T: PartialOrd + Clone + Copy + Display,
+    U: Num + PartialOrd + Clone + Copy,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.print())
     }
 }
 
-impl<T, U> IntervalCollection<T, U>
+impl<T, U> Default for IntervalCollection<T, U>
 where
-    T: Num + PartialOrd + Clone + Copy + Display,
-    U: Num + PartialOrd + Clone + Copy + Display + std::iter::Sum,
+    T: PartialOrd + Clone + Copy,
+    U: Num + PartialOrd + Clone + Copy,
 {
-    // Assumes ICs are always sorted by combine_intervals
-
-    pub fn new() -> Self {
-        IntervalCollection {
-            intervals: Vec::new(),
-        }
+    fn default() -> Self {
+        Self::new()
     }
+}
 
+impl<T, U> IntervalCollection<T, U>
+where
+    T: PartialOrd + Clone + Copy + Display,
+    U: Num + PartialOrd + Clone + Copy,
+{
     pub fn print(&self) -> String {
         format!(
             "IntervalCollection ({}x between {} and {})",
@@ -63,6 +88,20 @@ where
             self.get_ub()
         )
     }
+}
+
+impl<T, U> IntervalCollection<T, U>
+where
+    T: PartialOrd + Clone + Copy,
+    U: Num + PartialOrd + Clone + Copy,
+{
+    // Assumes ICs are always sorted by combine_intervals
+
+    pub fn new() -> Self {
+        IntervalCollection {
+            intervals: Vec::new(),
+        }
+    }
 
     pub fn get_lb(&self) -> T {
         // Todo: properly deal with empty collection
@@ -78,6 +117,10 @@ where
         self.intervals.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
     pub fn get_bounds(&self) -> (T, T) {
         // Todo: properly deal with empty collection
         (
@@ -88,7 +131,7 @@ where
 
     pub fn contains_num(&self, num: T) -> bool {
         for interval in self.intervals.iter() {
-            if interval.contains(num) {
+            if interval.contains(&num) {
                 return true;
             }
         }
@@ -97,25 +140,24 @@ where
 
     pub fn get_value(&self, num: T) -> U {
         for interval in self.intervals.iter() {
-            if interval.contains(num) {
+            if interval.contains(&num) {
                 return interval.get_value();
             }
         }
         U::zero()
     }
 
-    pub fn contains_interval(&self, interval: Interval<T, U>) -> bool {
-        let mut to_check = interval.clone();
+    pub fn contains_interval<I: crate::IntervalLike<T>>(&self, interval: I) -> bool {
+        let mut to_check = interval.to_base();
         for interval in self.intervals.iter() {
-            if interval.superset(to_check) {
+            if interval.superset(&to_check) {
                 return true;
-            } else if &to_check.get_lb() < &interval.get_lb() {
+            } else if to_check.get_lb() < interval.get_lb() {
                 return false;
-            } else if &to_check.get_lb() > &interval.get_ub() {
+            } else if to_check.get_lb() > interval.get_ub() {
                 continue;
             } else {
-                to_check =
-                    Interval::new(interval.get_ub(), to_check.get_ub(), to_check.get_value());
+                to_check = BaseInterval::new(interval.get_ub(), to_check.get_ub());
             }
         }
         false
@@ -127,9 +169,9 @@ where
         interval: Interval<T, U>,
     ) -> IntervalCollection<T, U> {
         let mut values = Vec::new();
-        let mut to_check = interval.clone();
+        let mut to_check = interval;
         for interval in self.intervals.iter() {
-            if interval.superset(to_check) {
+            if interval.superset(&to_check) {
                 let new = Interval::new(
                     to_check.get_lb(),
                     to_check.get_ub(),
@@ -137,9 +179,9 @@ where
                 );
                 values.push(new);
                 return IntervalCollection::from_vec(values);
-            } else if &to_check.get_lb() < &interval.get_lb() {
+            } else if to_check.get_lb() < interval.get_lb() {
                 return IntervalCollection::from_vec(values);
-            } else if &to_check.get_lb() > &interval.get_ub() {
+            } else if to_check.get_lb() > interval.get_ub() {
                 continue;
             } else {
                 to_check =
@@ -149,7 +191,7 @@ where
         IntervalCollection::from_vec(values)
     }
 
-    pub fn get_partially_overlaps_interval(&self, other: &Interval<T, U>) -> bool {
+    pub fn get_partially_overlaps_interval<I: crate::IntervalLike<T>>(&self, other: &I) -> bool {
         for interval in self.intervals.iter() {
             if interval.overlaps(other) {
                 return true;
@@ -182,7 +224,7 @@ where
     pub fn to_vec_as_set(&self) -> Vec<BaseInterval<T>> {
         // TODO: create unvalued BI (no U)
         let mut new = Vec::new();
-        if self.len() == 0 {
+        if self.is_empty() {
             return new;
         }
         let mut this_interval = self.intervals[0];
@@ -197,29 +239,255 @@ where
         new.push(this_interval.to_base());
         new
     }
+
+    // Sort the intervals, drop the ones that are empty or have a value of zero, and join
+    // neighbours that can be joined, like combine_intervals would have done
+    fn from_vec_normalized(mut vec: Vec<Interval<T, U>>) -> Self {
+        vec.retain(|x| x.get_lb() != x.get_ub() && x.get_value() != U::zero());
+        vec.sort_by(|a, b| a.get_lb().partial_cmp(&b.get_lb()).unwrap());
+        let mut new: Vec<Interval<T, U>> = Vec::with_capacity(vec.len());
+        for interval in vec {
+            match new.last_mut() {
+                Some(last) if last.can_join(&interval) => *last = last.join(interval),
+                _ => new.push(interval),
+            }
+        }
+        new.retain(|x| x.get_value() != U::zero());
+        IntervalCollection { intervals: new }
+    }
+
+    /// Apply `func` to the value of every interval. Neighbours that end up with the same value
+    /// are joined, and intervals with a value of zero are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intervalues::{Interval, combine_intervals};
+    ///
+    /// let this = combine_intervals(vec![Interval::new(0, 2, 1), Interval::new(1, 3, 2)]);
+    /// let capped = this.map_values(|x| x.min(2));
+    /// assert_eq!(capped.to_vec(), vec![Interval::new(0, 1, 1), Interval::new(1, 3, 2)]);
+    ///
+    /// let threshold = this.map_values(|x| if x >= 3 { 1 } else { 0 });
+    /// assert_eq!(threshold.to_vec(), vec![Interval::new(1, 2, 1)]);
+    /// ```
+    pub fn map_values<V, F>(&self, func: F) -> IntervalCollection<T, V>
+    where
+        V: Num + PartialOrd + Clone + Copy,
+        F: Fn(U) -> V,
+    {
+        let new = self
+            .intervals
+            .iter()
+            .map(|x| Interval::new(x.get_lb(), x.get_ub(), func(x.get_value())))
+            .collect();
+        IntervalCollection::from_vec_normalized(new)
+    }
+
+    /// Apply `func` to both bounds of every interval, such as a conversion of units. `func`
+    /// should be monotone: intervals that would overlap after the transformation are not
+    /// combined. Intervals that become empty are dropped, and neighbours that now touch with the
+    /// same value are joined.
+    pub fn map_bounds<T2, F>(&self, func: F) -> IntervalCollection<T2, U>
+    where
+        T2: PartialOrd + Clone + Copy,
+        F: Fn(T) -> T2,
+    {
+        let new = self
+            .intervals
+            .iter()
+            .map(|x| Interval::new(func(x.get_lb()), func(x.get_ub()), x.get_value()))
+            .collect();
+        IntervalCollection::from_vec_normalized(new)
+    }
+
+    /// New collection with only the intervals for which `pred` is true.
+    pub fn filter<F>(&self, pred: F) -> Self
+    where
+        F: Fn(&Interval<T, U>) -> bool,
+    {
+        let new = self.intervals.iter().filter(|x| pred(x)).copied().collect();
+        IntervalCollection::from_vec_normalized(new)
+    }
+
+    /// Keep only the intervals for which `pred` is true, in place.
+    pub fn retain<F>(&mut self, pred: F)
+    where
+        F: Fn(&Interval<T, U>) -> bool,
+    {
+        self.intervals.retain(|x| pred(x));
+        let new = core::mem::take(&mut self.intervals);
+        *self = IntervalCollection::from_vec_normalized(new);
+    }
+
+    // Walk over the bounds of both collections in order, calling `func` with every stretch
+    // between two consecutive bounds and the summed value of each collection on it. Values are
+    // added before they are removed at the same point, so unsigned values never go below zero.
+    fn sweep<F>(&self, other: &IntervalCollection<T, U>, mut func: F)
+    where
+        F: FnMut(T, T, U, U),
+    {
+        let mut points: Vec<(T, bool, usize, U)> = Vec::new();
+        for (side, collection) in [self, other].into_iter().enumerate() {
+            for interval in collection.intervals.iter() {
+                let (lb, ub, val) = interval.to_tuple();
+                points.push((lb, true, side, val));
+                points.push((ub, false, side, val));
+            }
+        }
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap().then(b.1.cmp(&a.1)));
+        let mut sums = [U::zero(); 2];
+        for (idx, &(point, enter, side, val)) in points.iter().enumerate() {
+            sums[side] = if enter {
+                sums[side] + val
+            } else {
+                sums[side] - val
+            };
+            match points.get(idx + 1) {
+                Some(next) if next.0 != point => func(point, next.0, sums[0], sums[1]),
+                _ => (),
+            }
+        }
+    }
+
+    /// The same step function in its canonical form: sorted pieces that do not overlap, where
+    /// overlapping values are added up, neighbours with the same value are joined and empty
+    /// pieces or pieces with a value of zero are dropped. This is the form combine_intervals
+    /// returns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intervalues::{Interval, IntervalCollection, combine_intervals};
+    ///
+    /// let raw = IntervalCollection::from_vec(vec![Interval::new(1, 3, 2), Interval::new(0, 1, 2)]);
+    /// let combined = combine_intervals(vec![Interval::new(0, 3, 2)]);
+    /// assert_ne!(raw, combined);
+    /// assert_eq!(raw.canonicalize(), combined);
+    /// assert!(raw.equivalent(&combined));
+    /// ```
+    pub fn canonicalize(&self) -> Self {
+        let mut new = Vec::with_capacity(self.len());
+        self.sweep(&IntervalCollection::new(), |lb, ub, val, _| {
+            if val != U::zero() {
+                new.push(Interval::new(lb, ub, val));
+            }
+        });
+        IntervalCollection::from_vec_normalized(new)
+    }
+
+    /// Whether both collections represent the same step function, even if their intervals are
+    /// split, ordered or overlapping in a different way.
+    pub fn equivalent(&self, other: &IntervalCollection<T, U>) -> bool {
+        self.canonicalize() == other.canonicalize()
+    }
+
+    /// Whether the values of both collections differ by at most `tolerance` at every point,
+    /// for example to ignore rounding noise in Decimal or float values. Bounds are compared
+    /// exactly, and gaps count as a value of zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intervalues::{Interval, combine_intervals};
+    ///
+    /// let this = combine_intervals(vec![Interval::new(0, 3, 0.3)]);
+    /// let that = combine_intervals(vec![Interval::new(0, 3, 0.1), Interval::new(0, 3, 0.2)]);
+    /// assert!(!this.equivalent(&that));
+    /// assert!(this.approx_eq(&that, 1e-12));
+    /// ```
+    pub fn approx_eq(&self, other: &IntervalCollection<T, U>, tolerance: U) -> bool {
+        let mut out = true;
+        self.sweep(other, |_, _, a, b| {
+            let diff = if a > b { a - b } else { b - a };
+            out &= diff <= tolerance;
+        });
+        out
+    }
 }
 
 impl<T, U> IntervalCollection<T, U>
 where
-    T: Num + PartialOrd + Clone + Copy + Display,
-    U: Num + PartialOrd + Clone + Copy + std::iter::Sum + From<T> + Display,
+    T: Bound + Copy,
+    U: Num + PartialOrd + Clone + Copy,
+    T::Width: WidthMul<U>,
+    <T::Width as WidthMul<U>>::Output: Default + Add<Output = <T::Width as WidthMul<U>>::Output>,
 {
-    pub fn total_value(&self) -> U {
+    pub fn total_value(&self) -> <T::Width as WidthMul<U>>::Output {
+        // Folded from zero, since the Sum of IntFloat starts counting at one
         self.intervals
             .iter()
-            .map(|x| <T as CastInto<U>>::cast_into(x.get_width()) * x.get_value())
-            .sum()
+            .fold(Default::default(), |acc, x| acc + x.get_total_value())
     }
 }
 
 impl<T, U> IntervalCollection<T, U>
 where
-    T: Num + PartialOrd + Clone + Copy + Display,
-    U: Num + PartialOrd + Clone + Copy + ToPrimitive + std::iter::Sum + Display,
+    T: Bound + Copy,
+    T::Width: Clone,
+    U: Num + PartialOrd + Clone + Copy,
+{
+    /// The collection moved by `delta`, such as a change of time zone.
+    pub fn shift(&self, delta: T::Width) -> Self {
+        let new = self
+            .intervals
+            .iter()
+            .map(|x| x.shift(delta.clone()))
+            .collect();
+        IntervalCollection::from_vec_normalized(new)
+    }
+}
+
+impl<T, U> IntervalCollection<T, U>
+where
+    T: Num + PartialOrd + Clone + Copy,
+    U: Num + PartialOrd + Clone + Copy,
+{
+    /// The collection with all bounds multiplied by `factor`, and the values changed as given by
+    /// `values`. A negative factor mirrors the collection around zero, which reverses the order
+    /// of the intervals. None when scaling by zero with `ValueScaling::Integral`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intervalues::{Interval, IntervalCollection, ValueScaling};
+    ///
+    /// let this = IntervalCollection::from_vec(vec![
+    ///     Interval::new(0.0, 1.0, 1.0),
+    ///     Interval::new(1.0, 3.0, 2.0),
+    /// ]);
+    /// let that = this.scale(-2.0, ValueScaling::Integral).unwrap();
+    /// assert_eq!(that.get_bounds(), (-6.0, 0.0));
+    /// assert_eq!(that.to_vec()[0], Interval::new(-6.0, -2.0, 1.0));
+    /// assert_eq!(that.total_value(), this.total_value());
+    /// ```
+    pub fn scale(&self, factor: T, values: ValueScaling) -> Option<Self>
+    where
+        T: WidthMul<U, Output = U>,
+    {
+        let new = self
+            .intervals
+            .iter()
+            .map(|x| x.scale(factor, values))
+            .collect::<Option<_>>()?;
+        Some(IntervalCollection::from_vec_normalized(new))
+    }
+
+    /// The collection mirrored around zero, keeping the values.
+    pub fn reflect(&self) -> Self {
+        let new = self.intervals.iter().map(|x| x.reflect()).collect();
+        IntervalCollection::from_vec_normalized(new)
+    }
+}
+
+impl<T, U> IntervalCollection<T, U>
+where
+    T: PartialOrd + Clone + Copy,
+    U: Num + PartialOrd + Clone + Copy + ToPrimitive,
 {
     pub fn to_vec_as_counter(&self) -> Vec<Interval<T, usize>> {
         let mut new = Vec::new();
-        if self.len() == 0 {
+        if self.is_empty() {
             return new;
         }
         let mut this_interval = self.intervals[0].val_to_count();
@@ -241,6 +509,482 @@ where
     }
 }
 
+impl<T, U> IntervalCollection<T, U>
+where
+    T: PartialOrd + Clone + Copy + ToPrimitive,
+    U: Num + PartialOrd + Clone + Copy + ToPrimitive,
+{
+    /// Bounds and values of all intervals as f64, or None if any of them can not be represented
+    /// as an f64.
+    pub fn to_f64_vec(&self) -> Option<Vec<(f64, f64, f64)>> {
+        self.intervals.iter().map(|x| x.to_f64()).collect()
+    }
+
+    /// Running integral of the collection, or None if a bound or value can not be represented as
+    /// an f64. See `Cumulative`.
+    pub fn cumulative(&self) -> Option<Cumulative<T>> {
+        Cumulative::new(self)
+    }
+
+    /// Total value (width × value) inside the trailing window `[x - width, x]`, for every `x`.
+    /// The result is exact: it only has corners where a piece enters or leaves the window. None
+    /// if a bound or value can not be represented as an f64.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intervalues::{Interval, combine_intervals};
+    ///
+    /// let occupancy = combine_intervals(vec![Interval::new(0, 4, 1), Interval::new(2, 4, 2)]);
+    /// let rolling = occupancy.rolling_sum(2).unwrap();
+    /// assert_eq!(rolling.get_value(1.0), 1.0);
+    /// assert_eq!(rolling.get_value(4.0), 6.0);
+    /// assert_eq!(rolling.get_value(5.0), 3.0);
+    /// assert_eq!(rolling.get_value(7.0), 0.0);
+    /// ```
+    pub fn rolling_sum(&self, width: T) -> Option<PiecewiseLinear> {
+        let width = width.to_f64()?;
+        let cumulative = self.cumulative()?;
+        let mut corners: Vec<f64> = cumulative
+            .get_points()
+            .into_iter()
+            .flat_map(|(x, _)| [x, x + width])
+            .collect();
+        corners.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        corners.dedup();
+        let points = corners
+            .into_iter()
+            .map(|x| {
+                let sum = cumulative.get_value_f64(x) - cumulative.get_value_f64(x - width);
+                (x, sum)
+            })
+            .collect();
+        Some(PiecewiseLinear::new(points))
+    }
+
+    /// Mean value inside the trailing window `[x - width, x]`, for every `x`. See `rolling_sum`.
+    pub fn rolling_mean(&self, width: T) -> Option<PiecewiseLinear> {
+        let width_f = width.to_f64()?;
+        Some(self.rolling_sum(width)?.map_values(|x| x / width_f))
+    }
+
+    /// Convolution with a box kernel of `width` and a total weight of one, centered around zero.
+    /// This is the mean value inside the window `[x - width / 2, x + width / 2]`.
+    pub fn convolve_box(&self, width: T) -> Option<PiecewiseLinear> {
+        let width_f = width.to_f64()?;
+        Some(self.rolling_mean(width)?.shift(-width_f / 2.0))
+    }
+
+    // Walk over the pieces of both collections in a single merge, calling `func` with the width
+    // of every stretch where both are constant and the difference of their values there. Gaps
+    // count as a value of zero.
+    fn merge_difference<F>(&self, other: &IntervalCollection<T, U>, mut func: F) -> Option<()>
+    where
+        F: FnMut(f64, f64),
+    {
+        let (a, b) = (self.to_f64_vec()?, other.to_f64_vec()?);
+        // Value at `x` and the next point where it changes, for the first piece not ending by `x`
+        let step = |piece: Option<&(f64, f64, f64)>, x: f64| match piece {
+            None => (0.0, f64::INFINITY),
+            Some(&(lb, _, _)) if lb > x => (0.0, lb),
+            Some(&(_, ub, val)) => (val, ub),
+        };
+        let mut x = match (a.first(), b.first()) {
+            (Some(first_a), Some(first_b)) => first_a.0.min(first_b.0),
+            (Some(first), None) | (None, Some(first)) => first.0,
+            (None, None) => return Some(()),
+        };
+        let (mut i, mut j) = (0, 0);
+        loop {
+            while i < a.len() && a[i].1 <= x {
+                i += 1;
+            }
+            while j < b.len() && b[j].1 <= x {
+                j += 1;
+            }
+            if i == a.len() && j == b.len() {
+                return Some(());
+            }
+            let (val_a, next_a) = step(a.get(i), x);
+            let (val_b, next_b) = step(b.get(j), x);
+            let next = next_a.min(next_b);
+            func(next - x, val_a - val_b);
+            x = next;
+        }
+    }
+
+    /// Integral of the absolute difference between this collection and `other`, seen as step
+    /// functions that are zero outside their pieces. None if a bound or value can not be
+    /// represented as an f64.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intervalues::{Interval, combine_intervals};
+    ///
+    /// let forecast = combine_intervals(vec![Interval::new(0, 4, 2)]);
+    /// let actual = combine_intervals(vec![Interval::new(0, 2, 2), Interval::new(2, 6, 1)]);
+    /// assert_eq!(forecast.l1_distance(&actual), Some(4.0));
+    /// assert_eq!(forecast.linf_distance(&actual), Some(1.0));
+    /// ```
+    pub fn l1_distance(&self, other: &IntervalCollection<T, U>) -> Option<f64> {
+        let mut total = 0.0;
+        self.merge_difference(other, |width, diff| total += width * diff.abs())?;
+        Some(total)
+    }
+
+    /// Square root of the integral of the squared difference between this collection and
+    /// `other`. See `l1_distance`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intervalues::{Interval, combine_intervals};
+    ///
+    /// let forecast = combine_intervals(vec![Interval::new(0, 4, 2)]);
+    /// let actual = combine_intervals(vec![Interval::new(0, 2, 2), Interval::new(2, 6, 1)]);
+    /// assert_eq!(forecast.l2_distance(&actual), Some(2.0));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn l2_distance(&self, other: &IntervalCollection<T, U>) -> Option<f64> {
+        let mut total = 0.0;
+        self.merge_difference(other, |width, diff| total += width * diff * diff)?;
+        Some(total.sqrt())
+    }
+
+    /// Largest absolute difference between this collection and `other` over a stretch of
+    /// non-zero width. See `l1_distance`.
+    pub fn linf_distance(&self, other: &IntervalCollection<T, U>) -> Option<f64> {
+        let mut largest: f64 = 0.0;
+        self.merge_difference(other, |width, diff| {
+            if width > 0.0 {
+                largest = largest.max(diff.abs());
+            }
+        })?;
+        Some(largest)
+    }
+
+    /// Convert the bounds and values to other numeric types, or None if any of them does not fit
+    /// in the new types. Intervals that become equal or empty by the conversion (for example when
+    /// truncating floats to integers) are kept as they are, so combine the result again if needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intervalues::{Interval, combine_intervals};
+    ///
+    /// let this = combine_intervals(vec![Interval::new(0, 300, 1), Interval::new(100, 200, 2)]);
+    /// let that = this.cast::<i16, u8>().unwrap();
+    /// assert_eq!(that.to_vec()[1], Interval::new(100i16, 200i16, 3u8));
+    /// assert!(this.cast::<u8, u8>().is_none());
+    /// ```
+    pub fn cast<T2, U2>(&self) -> Option<IntervalCollection<T2, U2>>
+    where
+        T2: PartialOrd + Clone + Copy + NumCast,
+        U2: Num + PartialOrd + Clone + Copy + NumCast,
+    {
+        let mut new = Vec::with_capacity(self.len());
+        for interval in self.intervals.iter() {
+            let (lb, ub, val) = interval.to_tuple();
+            new.push(Interval::new(T2::from(lb)?, T2::from(ub)?, U2::from(val)?));
+        }
+        Some(IntervalCollection::from_vec(new))
+    }
+}
+
+impl<T, U> IntervalCollection<T, U>
+where
+    T: Num + PartialOrd + Clone + Copy,
+    U: Num + PartialOrd + Clone + Copy,
+{
+    /// Highest value inside the trailing window `[x - width, x]`, for every `x`, as a step
+    /// function. Parts of the window outside the collection or in its gaps count as zero. The
+    /// maximum is kept in a monotone deque, so this takes linear time in the number of pieces.
+    /// Panics if `width` is negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intervalues::{Interval, combine_intervals};
+    ///
+    /// let this = combine_intervals(vec![Interval::new(0, 2, 3), Interval::new(2, 5, 1)]);
+    /// let that = this.rolling_max(2);
+    /// assert_eq!(that.to_vec(), vec![Interval::new(0, 4, 3), Interval::new(4, 7, 1)]);
+    /// ```
+    pub fn rolling_max(&self, width: T) -> Self {
+        assert!(width >= T::zero(), "width should not be negative");
+        if self.is_empty() {
+            return IntervalCollection::new();
+        }
+
+        // Segments from the start to the end of the collection, with its gaps filled by zeros. The
+        // first segment stands for everything before the collection, the last one for everything
+        // after it
+        let (lb, ub) = self.get_bounds();
+        let mut segments: Vec<(T, T, U)> = vec![(lb, lb, U::zero())];
+        for interval in self.intervals.iter() {
+            let (piece_lb, piece_ub, val) = interval.to_tuple();
+            let last_ub = segments.last().unwrap().1;
+            if last_ub < piece_lb {
+                segments.push((last_ub, piece_lb, U::zero()));
+            }
+            segments.push((piece_lb, piece_ub, val));
+        }
+        segments.push((ub, ub, U::zero()));
+
+        // A segment enters the window at its lowerbound and leaves it once the window has moved
+        // past its upperbound. Both happen in the order of the segments, so the deque holds the
+        // segments in the window that are not dominated by a later, higher segment
+        let (n, n_leave) = (segments.len(), segments.len() - 1);
+        let mut window: VecDeque<usize> = VecDeque::new();
+        let mut steps: Vec<(T, U)> = Vec::new();
+        let (mut enter, mut leave) = (0, 0);
+        while enter < n || leave < n_leave {
+            let pos = match (segments.get(enter), leave < n_leave) {
+                (Some(next), true) if next.0 < segments[leave].1 + width => next.0,
+                (Some(next), false) => next.0,
+                _ => segments[leave].1 + width,
+            };
+            while enter < n && segments[enter].0 == pos {
+                while window
+                    .back()
+                    .is_some_and(|x| segments[*x].2 <= segments[enter].2)
+                {
+                    window.pop_back();
+                }
+                window.push_back(enter);
+                enter += 1;
+            }
+            while leave < n_leave && segments[leave].1 + width == pos {
+                if window.front() == Some(&leave) {
+                    window.pop_front();
+                }
+                leave += 1;
+            }
+            steps.push((pos, segments[*window.front().unwrap()].2));
+        }
+
+        let new = steps
+            .windows(2)
+            .map(|x| Interval::new(x[0].0, x[1].0, x[0].1))
+            .collect();
+        IntervalCollection::from_vec_normalized(new)
+    }
+}
+
+// Summary of the pieces of a collection inside a single bin
+struct Bin<U, V> {
+    integral: V,
+    max: U,
+    start: U,
+}
+
+impl<T, U> IntervalCollection<T, U>
+where
+    T: Bound + Copy,
+    U: Num + PartialOrd + Clone + Copy,
+    T::Width: WidthMul<U>,
+    <T::Width as WidthMul<U>>::Output: Default + Add<Output = <T::Width as WidthMul<U>>::Output>,
+{
+    // Walk through the bins and the pieces together, so every piece is only visited for the bins
+    // that it overlaps
+    fn bins(&self, edges: &[T]) -> Vec<Bin<U, <T::Width as WidthMul<U>>::Output>> {
+        let mut out = Vec::with_capacity(edges.len().saturating_sub(1));
+        let mut first = 0;
+        for edge in edges.windows(2) {
+            let (lb, ub) = (edge[0], edge[1]);
+            while first < self.intervals.len() && self.intervals[first].get_ub() <= lb {
+                first += 1;
+            }
+            // Parts of the pieces inside the bin, clipped to its edges
+            let parts = || {
+                self.intervals[first..]
+                    .iter()
+                    .take_while(move |x| x.get_lb() < ub)
+                    .filter_map(move |x| {
+                        let (piece_lb, piece_ub, val) = x.to_tuple();
+                        let part_lb = if piece_lb > lb { piece_lb } else { lb };
+                        let part_ub = if piece_ub < ub { piece_ub } else { ub };
+                        (part_lb < part_ub).then_some((part_lb, part_ub, val))
+                    })
+            };
+            let mut covered_until = lb;
+            let mut gaps = false;
+            let mut max: Option<U> = None;
+            for (part_lb, part_ub, val) in parts() {
+                gaps |= part_lb > covered_until;
+                covered_until = part_ub;
+                max = match max {
+                    Some(x) if val > x => Some(val),
+                    None => Some(val),
+                    kept => kept,
+                };
+            }
+            gaps |= covered_until < ub;
+            let start = match parts().next() {
+                Some((part_lb, _, val)) if part_lb <= lb => val,
+                _ => U::zero(),
+            };
+            // Gaps inside the bin have a value of zero
+            let max = match max {
+                Some(max) if !gaps || max > U::zero() => max,
+                _ => U::zero(),
+            };
+            // Folded from zero, since the Sum of IntFloat starts counting at one
+            let integral = parts()
+                .map(|(part_lb, part_ub, val)| Bound::width(&part_lb, &part_ub).width_mul(val))
+                .fold(Default::default(), |acc, x| acc + x);
+            out.push(Bin {
+                integral,
+                max,
+                start,
+            });
+        }
+        out
+    }
+
+    /// Integral (width × value) of the collection between each pair of consecutive `edges`, which
+    /// should be increasing. Pieces that cross an edge are split exactly between the bins. Like
+    /// `total_value`, for time bounds these integrals are durations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intervalues::{Interval, combine_intervals};
+    ///
+    /// let this = combine_intervals(vec![Interval::new(0, 10, 1), Interval::new(5, 6, 3)]);
+    /// assert_eq!(this.histogram(&[0, 5, 8, 20]), vec![5, 6, 2]);
+    /// ```
+    pub fn histogram(&self, edges: &[T]) -> Vec<<T::Width as WidthMul<U>>::Output> {
+        self.bins(edges).into_iter().map(|x| x.integral).collect()
+    }
+}
+
+impl<T, U> IntervalCollection<T, U>
+where
+    T: Bound + Copy,
+    T::Width: Clone + Period + WidthMul<U, Output = U>,
+    U: Num + PartialOrd + Clone + Copy + Default,
+{
+    fn resample_edges(&self, edges: &[T], mode: ResampleMode) -> Vec<U> {
+        self.bins(edges)
+            .into_iter()
+            .zip(edges.windows(2))
+            .map(|(bin, edge)| match mode {
+                ResampleMode::Mean => {
+                    bin.integral / Bound::width(&edge[0], &edge[1]).width_mul(U::one())
+                }
+                ResampleMode::Integral => bin.integral,
+                ResampleMode::Max => bin.max,
+                ResampleMode::Start => bin.start,
+            })
+            .collect()
+    }
+
+    // Edges from start to end, bin_width apart, with a shorter last bin if needed. Every edge is
+    // computed from the start, so rounding errors do not add up, and an edge that only misses
+    // the end by rounding is replaced by the end.
+    fn grid(start: T, end: T, bin_width: T::Width) -> Vec<T> {
+        assert!(
+            start.offset(&bin_width) > start,
+            "bin_width should be positive"
+        );
+        let mut edges = Vec::new();
+        for i in 0.. {
+            let edge = start.offset(&bin_width.repeat(i));
+            if edge >= end
+                || (i > 0
+                    && bin_width
+                        .is_rounding_error(&Bound::width(&edge, &end), &Bound::width(&start, &end)))
+            {
+                break;
+            }
+            edges.push(edge);
+        }
+        edges.push(end);
+        edges
+    }
+
+    /// Summarize the collection into bins of `bin_width` from `start` to `end`, one value per bin
+    /// as given by `mode`. If `bin_width` does not divide `end - start`, the last bin is shorter.
+    /// Panics if `bin_width` is not positive. This needs a width × value that is a value again,
+    /// such as integer bounds with float values, so for time bounds use `histogram` instead.
+    pub fn resample(&self, start: T, end: T, bin_width: T::Width, mode: ResampleMode) -> Vec<U> {
+        let edges = IntervalCollection::<T, U>::grid(start, end, bin_width);
+        self.resample_edges(&edges, mode)
+    }
+
+    /// Like `resample`, but as a collection with an interval for every bin, including the bins
+    /// with a value of zero.
+    pub fn resample_grid(&self, start: T, end: T, bin_width: T::Width, mode: ResampleMode) -> Self {
+        let edges = IntervalCollection::<T, U>::grid(start, end, bin_width);
+        let values = self.resample_edges(&edges, mode);
+        let new = edges
+            .windows(2)
+            .zip(values)
+            .map(|(edge, val)| Interval::new(edge[0], edge[1], val))
+            .collect();
+        IntervalCollection::from_vec(new)
+    }
+}
+
+#[cfg(feature = "rand")]
+impl<T, U> IntervalCollection<T, U>
+where
+    T: PartialOrd + Clone + Copy + ToPrimitive,
+    U: Num + PartialOrd + Clone + Copy + ToPrimitive,
+{
+    /// Draw a point with a probability proportional to value × width. This normalizes the
+    /// collection on every call, so use `sample_iter` to draw many points.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<f64, IntervalPdfError> {
+        Ok(IntervalPdf::new(self)?.sample(rng))
+    }
+
+    /// Endless iterator of points drawn with a probability proportional to value × width. The
+    /// distribution is built once, after which every sample is a binary search in its cdf.
+    pub fn sample_iter<'a, R: Rng + ?Sized>(
+        &self,
+        rng: &'a mut R,
+    ) -> Result<impl Iterator<Item = f64> + 'a, IntervalPdfError>
+    where
+        T: 'a,
+    {
+        let pdf = IntervalPdf::new(self)?;
+        Ok(core::iter::repeat_with(move || pdf.sample(rng)))
+    }
+
+    // Uniform distribution on the pieces with a positive value, which IntervalPdf would keep
+    fn set_pdf(&self) -> Result<IntervalPdf<T>, IntervalPdfError> {
+        let set: Vec<Interval<T, f64>> = self
+            .intervals
+            .iter()
+            .filter(|x| x.get_value() > U::zero())
+            .map(|x| Interval::new(x.get_lb(), x.get_ub(), 1.0))
+            .collect();
+        IntervalPdf::new(&IntervalCollection::from_vec(set))
+    }
+
+    /// Draw a point uniformly from everything covered by the collection with a positive value,
+    /// ignoring how large the values are. Like `sample`, this builds the distribution on every
+    /// call, so use `sample_set_iter` to draw many points.
+    pub fn sample_set<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<f64, IntervalPdfError> {
+        Ok(self.set_pdf()?.sample(rng))
+    }
+
+    /// Endless iterator of points drawn uniformly from everything covered by the collection with
+    /// a positive value. The distribution is built once.
+    pub fn sample_set_iter<'a, R: Rng + ?Sized>(
+        &self,
+        rng: &'a mut R,
+    ) -> Result<impl Iterator<Item = f64> + 'a, IntervalPdfError>
+    where
+        T: 'a,
+    {
+        let pdf = self.set_pdf()?;
+        Ok(core::iter::repeat_with(move || pdf.sample(rng)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -290,6 +1034,16 @@ mod tests {
         assert!(this.contains_interval(Interval::new(0, 3, 1)));
         assert!(this.contains_interval(Interval::new(1, 2, 6)));
         assert!(!this.contains_interval(Interval::new(-1, 2, 1)));
+        assert!(this.contains_interval(BaseInterval::new(1, 3)));
+        assert!(!this.contains_interval(BaseInterval::new(2, 4)));
+    }
+
+    #[test]
+    fn test_partially_overlaps() {
+        let this = combine_intervals::combine_intervals(get_input());
+        assert!(this.get_partially_overlaps_interval(&Interval::new(-1, 0, 1)));
+        assert!(this.get_partially_overlaps_interval(&BaseInterval::new(1, 2)));
+        assert!(!this.get_partially_overlaps_interval(&BaseInterval::new(4, 5)));
     }
 
     // #[test]
@@ -310,11 +1064,385 @@ mod tests {
         assert_eq!(this.total_value(), 6);
     }
 
+    #[test]
+    fn test_map_values() {
+        let this = combine_intervals::combine_intervals(get_input());
+        let that = this.map_values(|x| x * 10);
+        assert_eq!(that.to_vec()[1], Interval::new(1, 2, 30));
+        assert_eq!(that.len(), 3);
+
+        // Pieces that end up with equal values are joined, and zeros are dropped
+        let that = this.map_values(|x| if x >= 2 { 1 } else { 0 });
+        assert_eq!(that.to_vec(), vec![Interval::new(1, 3, 1)]);
+        let that = this.map_values(|x| (x - 1) as f64 / 2.0);
+        assert_eq!(that.to_vec()[0], Interval::new(1, 2, 1.0));
+        assert_eq!(that.get_bounds(), (1, 3));
+    }
+
+    #[test]
+    fn test_map_bounds() {
+        let this = combine_intervals::combine_intervals(get_input());
+        let that = this.map_bounds(|x| x as f64 * 2.5);
+        assert_eq!(that.get_bounds(), (0.0, 7.5));
+        assert_eq!(that.get_value(3.0), 3);
+
+        // Mirroring keeps the collection sorted, and rounding can make pieces empty
+        let that = this.map_bounds(|x| -x);
+        assert_eq!(that.to_vec()[0], Interval::new(-3, -2, 2));
+        let that = this.map_bounds(|x| x / 2);
+        assert_eq!(that.to_vec(), vec![Interval::new(0, 1, 3)]);
+    }
+
+    #[test]
+    fn test_filter_and_retain() {
+        let this = IntervalCollection::from_vec(vec![
+            Interval::new(0, 1, 1),
+            Interval::new(1, 2, 5),
+            Interval::new(2, 3, 1),
+            Interval::new(3, 4, 1),
+        ]);
+        let that = this.filter(|x| x.get_value() < 5);
+        assert_eq!(
+            that.to_vec(),
+            vec![Interval::new(0, 1, 1), Interval::new(2, 4, 1)]
+        );
+
+        let mut this = this;
+        this.retain(|x| x.get_lb() >= 1);
+        assert_eq!(
+            this.to_vec(),
+            vec![Interval::new(1, 2, 5), Interval::new(2, 4, 1)]
+        );
+    }
+
+    #[test]
+    fn test_shift_scale_reflect() {
+        let this = combine_intervals::combine_intervals(get_input());
+        let that = this.shift(10);
+        assert_eq!(that.get_bounds(), (10, 13));
+        assert_eq!(that.total_value(), this.total_value());
+
+        let that = this.scale(-2, ValueScaling::Density).unwrap();
+        assert_eq!(
+            that.to_vec(),
+            vec![
+                Interval::new(-6, -4, 2),
+                Interval::new(-4, -2, 3),
+                Interval::new(-2, 0, 1)
+            ]
+        );
+        assert_eq!(that.total_value(), 2 * this.total_value());
+        assert_eq!(Some(this.reflect()), this.scale(-1, ValueScaling::Integral));
+        assert_eq!(this.reflect().reflect(), this);
+        assert_eq!(this.scale(0, ValueScaling::Integral), None);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let this = combine_intervals::combine_intervals(get_input());
+        assert_eq!(this.histogram(&[0, 3]), vec![this.total_value()]);
+        assert_eq!(this.histogram(&[-5, 0, 1, 2, 3, 9]), vec![0, 1, 3, 2, 0]);
+
+        // Pieces split across bins are divided by the width of each part
+        let this = IntervalCollection::from_vec(vec![Interval::new(0.0, 1.0, 2.0)]);
+        assert_eq!(this.histogram(&[0.0, 0.25, 1.0, 2.0]), vec![0.5, 1.5, 0.0]);
+        assert!(this.histogram(&[0.5]).is_empty());
+    }
+
+    #[test]
+    fn test_resample() {
+        let this = IntervalCollection::from_vec(vec![
+            Interval::new(1.0, 2.0, 4.0),
+            Interval::new(2.0, 3.0, -1.0),
+            Interval::new(5.0, 6.0, 2.0),
+        ]);
+        let mean = this.resample(0.0, 7.0, 2.0, ResampleMode::Mean);
+        assert_eq!(mean, vec![2.0, -0.5, 1.0, 0.0]);
+        let max = this.resample(0.0, 7.0, 2.0, ResampleMode::Max);
+        assert_eq!(max, vec![4.0, 0.0, 2.0, 0.0]);
+        let start = this.resample(0.0, 7.0, 2.0, ResampleMode::Start);
+        assert_eq!(start, vec![0.0, -1.0, 0.0, 0.0]);
+
+        let grid = this.resample_grid(0.0, 7.0, 2.0, ResampleMode::Mean);
+        assert_eq!(grid.len(), 4);
+        assert_eq!(grid.to_vec()[3], Interval::new(6.0, 7.0, 0.0));
+        assert_eq!(grid.total_value(), this.total_value());
+
+        // Integer bounds with float values
+        let this = IntervalCollection::from_vec(vec![Interval::new(1i64, 4i64, 0.5f64)]);
+        let mean = this.resample(0, 6, 4, ResampleMode::Mean);
+        assert_eq!(mean, vec![0.375, 0.0]);
+        let grid = this.resample_grid(0, 6, 4, ResampleMode::Mean);
+        assert_eq!(grid.total_value(), 1.5);
+
+        // Float bin widths that do not divide exactly, without a sliver of a bin at the end
+        let this = IntervalCollection::from_vec(vec![Interval::new(0.0, 1.0, 1.0f64)]);
+        for (bin_width, count) in [(0.1, 10), (0.01, 100), (0.3, 4), (1.0 / 3.0, 3)] {
+            let mean = this.resample(0.0, 1.0, bin_width, ResampleMode::Mean);
+            assert_eq!(mean.len(), count);
+            assert!(mean.iter().all(|x| (x - 1.0).abs() < 1e-12));
+        }
+        let grid = this.resample_grid(0.0, 0.7, 0.1, ResampleMode::Mean);
+        assert_eq!(grid.len(), 7);
+        assert_eq!(grid.get_ub(), 0.7);
+    }
+
+    #[cfg(feature = "intfloat")]
+    #[test]
+    fn test_total_value_intfloat() {
+        use intfloat::IntFloat;
+
+        let this = IntervalCollection::from_vec(vec![Interval::new(
+            IntFloat::from(0.0, 0),
+            IntFloat::from(2.0, 0),
+            IntFloat::from(1.0, 0),
+        )]);
+        assert_eq!(this.total_value(), IntFloat::from(2.0, 0));
+    }
+
+    #[cfg(feature = "intfloat")]
+    #[test]
+    fn test_histogram_intfloat() {
+        use intfloat::IntFloat;
+
+        let this = IntervalCollection::from_vec(vec![Interval::new(
+            IntFloat::from(0.0, 0),
+            IntFloat::from(2.0, 0),
+            IntFloat::from(1.0, 0),
+        )]);
+        let edges = [IntFloat::from(0.0, 0), IntFloat::from(1.0, 0)];
+        assert_eq!(this.histogram(&edges), vec![IntFloat::from(1.0, 0)]);
+        let mean = this.resample(
+            IntFloat::from(0.0, 0),
+            IntFloat::from(2.0, 0),
+            IntFloat::from(2.0, 0),
+            ResampleMode::Mean,
+        );
+        assert_eq!(mean, vec![IntFloat::from(1.0, 0)]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_histogram_time() {
+        use std::time::{Duration, SystemTime};
+
+        let hour = Duration::from_secs(3600);
+        let start = SystemTime::UNIX_EPOCH;
+        let this = IntervalCollection::from_vec(vec![
+            Interval::new(start, start + 2 * hour, 1u32),
+            Interval::new(start + 3 * hour, start + 4 * hour, 2u32),
+        ]);
+        let edges = [start, start + hour, start + 4 * hour];
+        assert_eq!(this.histogram(&edges), vec![hour, 3 * hour]);
+    }
+
+    #[test]
+    fn test_rolling_sum() {
+        let this = combine_intervals::combine_intervals(get_input());
+        let sum = this.rolling_sum(1).unwrap();
+        assert_eq!(
+            sum.get_points(),
+            vec![(0.0, 0.0), (1.0, 1.0), (2.0, 3.0), (3.0, 2.0), (4.0, 0.0)]
+        );
+        assert_eq!(sum.get_value(1.5), 2.0);
+        let mean = this.rolling_mean(2).unwrap();
+        assert_eq!(mean.get_value(2.0), 2.0);
+        assert_eq!(mean.get_value(5.0), 0.0);
+
+        // The box kernel is centered, and the total value stays the same
+        let smooth = this.convolve_box(2).unwrap();
+        assert_eq!(smooth.get_value(1.5), 2.25);
+        assert_eq!(smooth.get_points()[0], (-1.0, 0.0));
+        assert_eq!(smooth.get_points().last(), Some(&(4.0, 0.0)));
+    }
+
+    #[test]
+    fn test_rolling_max() {
+        let this = IntervalCollection::from_vec(vec![
+            Interval::new(0, 1, 5),
+            Interval::new(1, 3, 2),
+            Interval::new(6, 7, 4),
+            Interval::new(7, 8, 1),
+        ]);
+        let that = this.rolling_max(2);
+        assert_eq!(
+            that.to_vec(),
+            vec![
+                Interval::new(0, 3, 5),
+                Interval::new(3, 5, 2),
+                Interval::new(6, 9, 4),
+                Interval::new(9, 10, 1)
+            ]
+        );
+        assert_eq!(this.rolling_max(0), this);
+
+        // Gaps and the area around the collection count as zero for negative values
+        let this = IntervalCollection::from_vec(vec![Interval::new(0, 4, -1)]);
+        assert_eq!(this.rolling_max(1).to_vec(), vec![Interval::new(1, 4, -1)]);
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let this = IntervalCollection::from_vec(vec![
+            Interval::new(2, 3, 2),
+            Interval::new(0, 2, 1),
+            Interval::new(1, 2, 2),
+            Interval::new(4, 4, 5),
+            Interval::new(5, 6, 0),
+        ]);
+        assert_eq!(this.canonicalize(), combine_intervals(get_input()));
+        assert!(this.equivalent(&combine_intervals(get_input())));
+        assert!(!this.equivalent(&IntervalCollection::new()));
+        let empty: IntervalCollection<i64, i64> = IntervalCollection::new();
+        assert_eq!(empty.canonicalize(), empty);
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let this = IntervalCollection::from_vec(vec![Interval::new(0, 4, 3u32)]);
+        let that = IntervalCollection::from_vec(vec![
+            Interval::new(0, 2, 3u32),
+            Interval::new(2, 4, 4u32),
+        ]);
+        assert!(this.approx_eq(&that, 1));
+        assert!(!this.approx_eq(&that, 0));
+        assert!(that.approx_eq(&this, 1));
+        let longer = IntervalCollection::from_vec(vec![Interval::new(0, 5, 3u32)]);
+        assert!(!this.approx_eq(&longer, 2));
+        assert!(this.approx_eq(&longer, 3));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_approx_eq_decimal() {
+        use rust_decimal::Decimal;
+
+        let third = Decimal::ONE / Decimal::from(3);
+        let this =
+            IntervalCollection::from_vec(vec![Interval::new(0, 3, third * Decimal::from(3))]);
+        let that = combine_intervals(vec![Interval::new(0, 3, Decimal::ONE)]);
+        assert!(!this.equivalent(&that));
+        assert!(this.approx_eq(&that, Decimal::new(1, 20)));
+    }
+
+    #[test]
+    fn test_distances() {
+        let this = combine_intervals(get_input());
+        let that = combine_intervals(vec![Interval::new(0, 3, 1), Interval::new(5, 9, 2)]);
+        // Differences of 0, 2 and 1 on [0, 3], and 2 on [5, 9]
+        assert_eq!(this.l1_distance(&that), Some(11.0));
+        assert_eq!(that.l1_distance(&this), Some(11.0));
+        assert_eq!(this.linf_distance(&that), Some(2.0));
+        assert_eq!(this.linf_distance(&this.map_values(|x| x + 1)), Some(1.0));
+        assert_eq!(this.l1_distance(&this), Some(0.0));
+        let empty = IntervalCollection::new();
+        assert_eq!(empty.l1_distance(&empty), Some(0.0));
+        assert_eq!(that.l1_distance(&empty), Some(11.0));
+        assert_eq!(empty.linf_distance(&that), Some(2.0));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_l2_distance() {
+        let this = combine_intervals(vec![Interval::new(0, 1, 3)]);
+        let that = combine_intervals(vec![Interval::new(1, 5, 2)]);
+        assert_eq!(this.l2_distance(&that), Some(5.0));
+        assert_eq!(this.l2_distance(&IntervalCollection::new()), Some(3.0));
+    }
+
+    #[test]
+    fn test_to_f64_vec_and_cast() {
+        let this = combine_intervals::combine_intervals(get_input());
+        assert_eq!(
+            this.to_f64_vec(),
+            Some(vec![(0.0, 1.0, 1.0), (1.0, 2.0, 3.0), (2.0, 3.0, 2.0)])
+        );
+        let that: IntervalCollection<f64, f32> = this.cast().unwrap();
+        assert_eq!(that.to_vec()[1], Interval::new(1.0, 2.0, 3.0));
+
+        // Values that do not fit fail the whole cast
+        let this =
+            IntervalCollection::from_vec(vec![Interval::new(0, 1, -1), Interval::new(1, 2, 1)]);
+        assert!(this.cast::<i64, u32>().is_none());
+        assert!(this.cast::<u8, i8>().is_some());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_total_value_time() {
+        use std::time::{Duration, Instant};
+
+        let start = Instant::now();
+        let hour = Duration::from_secs(3600);
+        let input = vec![
+            Interval::new(start, start + 8 * hour, 1.0),
+            Interval::new(start + 4 * hour, start + 12 * hour, 2.0),
+        ];
+        let this = combine_intervals::combine_intervals(input);
+        assert_eq!(this.len(), 3);
+        assert_eq!(this.get_value(start + 6 * hour), 3.0);
+        assert_eq!(this.total_value(), 24 * hour);
+    }
+
     #[test]
     fn test_len() {
         let this = IntervalCollection::from_vec(get_input());
         assert_eq!(this.len(), 2);
         let this = combine_intervals::combine_intervals(get_input());
         assert_eq!(this.len(), 3);
+        assert!(!this.is_empty());
+    }
+
+    #[test]
+    fn test_default() {
+        let this: IntervalCollection<i64, i64> = IntervalCollection::default();
+        assert!(this.is_empty());
+        assert_eq!(this, IntervalCollection::new());
+        assert_eq!(this.to_vec_as_set(), vec![]);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_sample() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let this = combine_intervals(vec![Interval::new(0, 1, 1), Interval::new(2, 3, 9)]);
+        let mut rng = StdRng::seed_from_u64(3);
+        let samples: Vec<f64> = this.sample_iter(&mut rng).unwrap().take(10000).collect();
+        let lower = samples.iter().filter(|x| **x <= 1.0).count() as f64 / 10000.0;
+        assert!((lower - 0.1).abs() < 0.02);
+        assert!(samples.iter().all(|x| *x <= 1.0 || *x >= 2.0));
+
+        let samples: Vec<f64> = this
+            .sample_set_iter(&mut rng)
+            .unwrap()
+            .take(10000)
+            .collect();
+        let lower = samples.iter().filter(|x| **x <= 1.0).count() as f64 / 10000.0;
+        assert!((lower - 0.5).abs() < 0.03);
+        assert!((0.0..=3.0).contains(&this.sample_set(&mut rng).unwrap()));
+
+        // Pieces with a negative value are not covered
+        let mixed = IntervalCollection::from_vec(vec![
+            Interval::new(0.0, 1.0, 1.0),
+            Interval::new(1.0, 2.0, -1.0),
+        ]);
+        assert!(mixed
+            .sample_set_iter(&mut rng)
+            .unwrap()
+            .take(2000)
+            .all(|x| (0.0..=1.0).contains(&x)));
+        let negative = IntervalCollection::from_vec(vec![Interval::new(0.0, 1.0, -1.0)]);
+        assert_eq!(
+            negative.sample_set(&mut rng),
+            Err(IntervalPdfError::ZeroMass)
+        );
+
+        let single = this.sample(&mut rng).unwrap();
+        assert!((0.0..=3.0).contains(&single));
+        assert!(IntervalCollection::<i64, i64>::new()
+            .sample(&mut rng)
+            .is_err());
     }
 }
@@ -0,0 +1,232 @@
+use crate::Interval;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::PartialOrd;
+use num_traits::Num;
+
+#[derive(Clone)]
+/// Index over raw (uncombined) Intervals, to find back which of the original intervals contain a
+/// point or overlap a range. Internally this is an augmented interval tree, stored implicitly in a
+/// Vec sorted on lowerbound, so queries take O(log n + k) for k results.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{Interval, IntervalIndex};
+///
+/// let input = vec![Interval::new(0, 2, 1), Interval::new(1, 3, 2), Interval::new(5, 6, 1)];
+/// let index = IntervalIndex::new(input);
+///
+/// assert_eq!(index.query_point_indices(1), vec![0, 1]);
+/// assert_eq!(index.query_point(5), vec![Interval::new(5, 6, 1)]);
+/// assert_eq!(index.query_overlap_indices(3, 5), vec![1, 2]);
+/// ```
+pub struct IntervalIndex<T, U> {
+    intervals: Vec<Interval<T, U>>,
+    positions: Vec<usize>,
+    slots: Vec<usize>,
+    max_ub: Vec<T>,
+}
+
+impl<T, U> IntervalIndex<T, U>
+where
+    T: PartialOrd + Clone + Copy,
+    U: Num + PartialOrd + Clone + Copy,
+{
+    pub fn new(intervals: Vec<Interval<T, U>>) -> Self {
+        let mut sorted: Vec<(usize, Interval<T, U>)> = intervals.into_iter().enumerate().collect();
+        sorted.sort_by(|a, b| a.1.get_lb().partial_cmp(&b.1.get_lb()).unwrap());
+        let (positions, intervals): (Vec<usize>, Vec<Interval<T, U>>) = sorted.into_iter().unzip();
+
+        let mut slots = vec![0; positions.len()];
+        for (slot, position) in positions.iter().enumerate() {
+            slots[*position] = slot;
+        }
+
+        let mut max_ub: Vec<T> = intervals.iter().map(|x| x.get_ub()).collect();
+        Self::augment(&intervals, &mut max_ub, 0, intervals.len());
+        IntervalIndex {
+            intervals,
+            positions,
+            slots,
+            max_ub,
+        }
+    }
+
+    // Node for the range [lo, hi) sits at its midpoint and stores the max ub of the whole range
+    fn augment(intervals: &[Interval<T, U>], max_ub: &mut [T], lo: usize, hi: usize) -> Option<T> {
+        if lo >= hi {
+            return None;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let mut this = intervals[mid].get_ub();
+        for child in [
+            Self::augment(intervals, max_ub, lo, mid),
+            Self::augment(intervals, max_ub, mid + 1, hi),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if child > this {
+                this = child;
+            }
+        }
+        max_ub[mid] = this;
+        Some(this)
+    }
+
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    fn collect_overlaps(&self, query: &Interval<T, U>, lo: usize, hi: usize, out: &mut Vec<usize>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        if self.max_ub[mid] < query.get_lb() {
+            // Nothing in this subtree reaches the query
+            return;
+        }
+        self.collect_overlaps(query, lo, mid, out);
+        if self.intervals[mid].get_lb() > query.get_ub() {
+            // Everything to the right starts after the query
+            return;
+        }
+        if self.intervals[mid].overlaps(query) {
+            out.push(mid);
+        }
+        self.collect_overlaps(query, mid + 1, hi, out);
+    }
+
+    fn overlap_slots(&self, lb: T, ub: T) -> Vec<usize> {
+        let query = Interval::new(lb, ub, U::one());
+        let mut out = Vec::new();
+        self.collect_overlaps(&query, 0, self.intervals.len(), &mut out);
+        out
+    }
+
+    fn to_indices(&self, slots: Vec<usize>) -> Vec<usize> {
+        let mut out: Vec<usize> = slots.into_iter().map(|x| self.positions[x]).collect();
+        out.sort();
+        out
+    }
+
+    /// Positions (in the original input) of the intervals that contain `num`.
+    pub fn query_point_indices(&self, num: T) -> Vec<usize> {
+        let slots = self
+            .overlap_slots(num, num)
+            .into_iter()
+            .filter(|x| self.intervals[*x].contains(&num))
+            .collect();
+        self.to_indices(slots)
+    }
+
+    /// The original intervals that contain `num`, in input order.
+    pub fn query_point(&self, num: T) -> Vec<Interval<T, U>> {
+        self.query_point_indices(num)
+            .into_iter()
+            .map(|x| self.get(x))
+            .collect()
+    }
+
+    /// Positions (in the original input) of the intervals that overlap the range `[lb, ub]`.
+    pub fn query_overlap_indices(&self, lb: T, ub: T) -> Vec<usize> {
+        let slots = self.overlap_slots(lb, ub);
+        self.to_indices(slots)
+    }
+
+    /// The original intervals that overlap the range `[lb, ub]`, in input order.
+    pub fn query_overlap(&self, lb: T, ub: T) -> Vec<Interval<T, U>> {
+        self.query_overlap_indices(lb, ub)
+            .into_iter()
+            .map(|x| self.get(x))
+            .collect()
+    }
+
+    /// The interval at position `index` of the original input.
+    pub fn get(&self, index: usize) -> Interval<T, U> {
+        self.intervals[self.slots[index]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_input() -> Vec<Interval<i64, i64>> {
+        let input: Vec<[i64; 3]> = vec![[4, 8, 1], [0, 2, 1], [1, 3, 2], [6, 7, 3], [2, 9, 1]];
+        input
+            .iter()
+            .map(|x| Interval::new(x[0], x[1], x[2]))
+            .collect()
+    }
+
+    fn brute_force_point(input: &[Interval<i64, i64>], num: i64) -> Vec<usize> {
+        (0..input.len())
+            .filter(|x| input[*x].contains(&num))
+            .collect()
+    }
+
+    fn brute_force_overlap(input: &[Interval<i64, i64>], lb: i64, ub: i64) -> Vec<usize> {
+        let query = Interval::new(lb, ub, 1);
+        (0..input.len())
+            .filter(|x| input[*x].overlaps(&query))
+            .collect()
+    }
+
+    #[test]
+    fn test_query_point() {
+        let index = IntervalIndex::new(get_input());
+        assert_eq!(index.query_point_indices(1), vec![1, 2]);
+        assert_eq!(index.query_point_indices(2), vec![1, 2, 4]);
+        assert_eq!(index.query_point_indices(7), vec![0, 3, 4]);
+        assert!(index.query_point_indices(10).is_empty());
+        assert_eq!(
+            index.query_point(3),
+            vec![Interval::new(1, 3, 2), Interval::new(2, 9, 1)]
+        );
+    }
+
+    #[test]
+    fn test_query_overlap() {
+        let index = IntervalIndex::new(get_input());
+        assert_eq!(index.query_overlap_indices(3, 4), vec![0, 2, 4]);
+        assert_eq!(index.query_overlap_indices(-5, 0), vec![1]);
+        assert_eq!(index.query_overlap_indices(-1, 20), vec![0, 1, 2, 3, 4]);
+        assert!(index.query_overlap_indices(10, 20).is_empty());
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let input = get_input();
+        let index = IntervalIndex::new(input.clone());
+        for num in -1..11 {
+            assert_eq!(
+                index.query_point_indices(num),
+                brute_force_point(&input, num)
+            );
+            for width in 0..4 {
+                assert_eq!(
+                    index.query_overlap_indices(num, num + width),
+                    brute_force_overlap(&input, num, num + width)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_and_len() {
+        let index = IntervalIndex::new(get_input());
+        assert_eq!(index.len(), 5);
+        assert_eq!(index.get(3), Interval::new(6, 7, 3));
+
+        let empty: IntervalIndex<i64, i64> = IntervalIndex::new(Vec::new());
+        assert!(empty.is_empty());
+        assert!(empty.query_point_indices(0).is_empty());
+    }
+}
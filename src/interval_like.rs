@@ -0,0 +1,140 @@
+use crate::allen_relation::relation_between;
+use crate::{AllenRelation, BaseInterval, Bound};
+use core::cmp::PartialOrd;
+
+/// Shared behaviour of anything shaped like an interval: something with a lowerbound and an
+/// upperbound. Only `get_lb` and `get_ub` have to be implemented, everything else follows from
+/// them. Both BaseInterval and Interval implement this, and it can be implemented for your own
+/// types so they can be used with IntervalCollection and the combine functions.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{AllenRelation, BaseInterval, IntervalLike};
+///
+/// struct Shift {
+///     start: i64,
+///     end: i64,
+/// }
+///
+/// impl IntervalLike<i64> for Shift {
+///     fn get_lb(&self) -> i64 {
+///         self.start
+///     }
+///
+///     fn get_ub(&self) -> i64 {
+///         self.end
+///     }
+/// }
+///
+/// let shift = Shift { start: 8, end: 17 };
+/// assert_eq!(IntervalLike::get_width(&shift), 9);
+/// assert!(IntervalLike::contains(&shift, &12));
+/// assert_eq!(shift.relation(&BaseInterval::new(17, 22)), AllenRelation::Meets);
+/// ```
+pub trait IntervalLike<T: PartialOrd + Clone> {
+    fn get_lb(&self) -> T;
+
+    fn get_ub(&self) -> T;
+
+    fn get_bounds(&self) -> (T, T) {
+        (self.get_lb(), self.get_ub())
+    }
+
+    fn get_width(&self) -> T::Width
+    where
+        T: Bound,
+    {
+        T::width(&self.get_lb(), &self.get_ub())
+    }
+
+    fn contains(&self, num: &T) -> bool {
+        (*num >= self.get_lb()) && (*num <= self.get_ub())
+    }
+
+    fn relation<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> AllenRelation {
+        relation_between(
+            &self.get_lb(),
+            &self.get_ub(),
+            &other.get_lb(),
+            &other.get_ub(),
+        )
+    }
+
+    fn superset<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        self.relation(other).is_superset()
+    }
+
+    fn subset<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        self.relation(other).is_subset()
+    }
+
+    fn left_overlaps<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        self.relation(other).is_left_overlapping()
+    }
+
+    fn right_overlaps<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        self.relation(other).is_right_overlapping()
+    }
+
+    fn overlaps<I: IntervalLike<T> + ?Sized>(&self, other: &I) -> bool {
+        self.relation(other).is_overlapping()
+    }
+
+    fn to_base(&self) -> BaseInterval<T> {
+        BaseInterval::new(self.get_lb(), self.get_ub())
+    }
+}
+
+/// An IntervalLike that also carries a value, so it can be fed to combine_intervals.
+pub trait ValuedIntervalLike<T: PartialOrd + Clone, U>: IntervalLike<T> {
+    fn get_value(&self) -> U;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Interval;
+
+    fn describe<I: IntervalLike<i64>>(interval: &I) -> (i64, i64, i64) {
+        let (lb, ub) = interval.get_bounds();
+        (lb, ub, interval.get_width())
+    }
+
+    #[test]
+    fn test_generic_bounds() {
+        let a = BaseInterval::new(3, 7);
+        let b = Interval::new(3, 7, 2);
+        assert_eq!(describe(&a), (3, 7, 4));
+        assert_eq!(describe(&a), describe(&b));
+        assert_eq!(IntervalLike::to_base(&b), a);
+    }
+
+    #[test]
+    fn test_mixed_predicates() {
+        let a = BaseInterval::new(0, 4);
+        let b = Interval::new(4, 6, 2);
+        let c = Interval::new(1, 3, 5);
+        assert_eq!(IntervalLike::relation(&a, &b), AllenRelation::Meets);
+        assert!(IntervalLike::overlaps(&a, &b));
+        assert!(IntervalLike::left_overlaps(&a, &b));
+        assert!(IntervalLike::right_overlaps(&b, &a));
+        assert!(IntervalLike::superset(&a, &c));
+        assert!(IntervalLike::subset(&c, &a));
+        assert!(!IntervalLike::overlaps(&b, &c));
+        assert!(IntervalLike::contains(&b, &5));
+    }
+
+    #[test]
+    fn test_agrees_with_inherent() {
+        let a = Interval::new(0, 4, 1);
+        for (lb, ub) in [(0, 4), (1, 3), (2, 6), (4, 5), (5, 6), (-2, 0)] {
+            let b = Interval::new(lb, ub, 1);
+            assert_eq!(IntervalLike::relation(&a, &b), a.relation(&b));
+            assert_eq!(IntervalLike::overlaps(&a, &b), a.overlaps(&b));
+            assert_eq!(IntervalLike::superset(&a, &b), a.superset(&b));
+        }
+        assert_eq!(ValuedIntervalLike::get_value(&a), 1);
+        assert_eq!(ValuedIntervalLike::get_value(&BaseInterval::new(0, 2)), 1);
+    }
+}
@@ -0,0 +1,260 @@
+use crate::BaseInterval;
+use alloc::vec::Vec;
+use core::cmp::PartialOrd;
+
+#[derive(Clone, PartialEq)]
+/// Map from disjoint ranges to arbitrary values, for payloads that are not numbers (labels, enums,
+/// configuration structs). Ranges are BaseIntervals; inserting a range either overrides what was
+/// there, or merges with it using a closure. Neighbouring ranges with equal values are coalesced,
+/// so the map is always stored in its most compact form.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{BaseInterval, IntervalMap};
+///
+/// let mut map = IntervalMap::new();
+/// map.insert(BaseInterval::new(0, 10), "off");
+/// map.insert(BaseInterval::new(3, 5), "on");
+///
+/// assert_eq!(map.get(1), Some(&"off"));
+/// assert_eq!(map.get(4), Some(&"on"));
+/// assert_eq!(map.get(12), None);
+/// assert_eq!(map.len(), 3);
+/// ```
+pub struct IntervalMap<T, V> {
+    entries: Vec<(BaseInterval<T>, V)>,
+}
+
+impl<T, V> Default for IntervalMap<T, V>
+where
+    T: PartialOrd + Clone + Copy,
+    V: Clone + PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, V> IntervalMap<T, V>
+where
+    T: PartialOrd + Clone + Copy,
+    V: Clone + PartialEq,
+{
+    // Entries are kept sorted, disjoint (apart from shared borders) and coalesced
+
+    pub fn new() -> Self {
+        IntervalMap {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn from_vec(vec: Vec<(BaseInterval<T>, V)>) -> Self {
+        let mut map = Self::new();
+        for (interval, value) in vec.into_iter() {
+            map.insert(interval, value);
+        }
+        map
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Set `value` on `interval`, overriding any values that were there before.
+    pub fn insert(&mut self, interval: BaseInterval<T>, value: V) {
+        self.insert_with(interval, value, |_, new| new.clone())
+    }
+
+    /// Set `value` on `interval`. Where a value was already present, `merge(old, new)` decides the
+    /// value of that part; parts of `interval` without a previous value get `value` itself.
+    pub fn insert_with<F>(&mut self, interval: BaseInterval<T>, value: V, merge: F)
+    where
+        F: Fn(&V, &V) -> V,
+    {
+        let (lb, ub) = interval.get_bounds();
+        if lb == ub {
+            return;
+        }
+
+        let mut new = Vec::new();
+        let mut cursor = lb;
+        for (this, this_value) in self.entries.iter() {
+            let (this_lb, this_ub) = this.get_bounds();
+            if this_ub <= lb || this_lb >= ub {
+                new.push((*this, this_value.clone()));
+                continue;
+            }
+
+            // Part of the existing range before and after the inserted range
+            if this_lb < lb {
+                new.push((BaseInterval::new(this_lb, lb), this_value.clone()));
+            }
+            if this_ub > ub {
+                new.push((BaseInterval::new(ub, this_ub), this_value.clone()));
+            }
+
+            // Gap before the overlapping part, and the overlapping part itself
+            let overlap_lb = if this_lb > lb { this_lb } else { lb };
+            let overlap_ub = if this_ub < ub { this_ub } else { ub };
+            if cursor < overlap_lb {
+                new.push((BaseInterval::new(cursor, overlap_lb), value.clone()));
+            }
+            new.push((
+                BaseInterval::new(overlap_lb, overlap_ub),
+                merge(this_value, &value),
+            ));
+            cursor = overlap_ub;
+        }
+        if cursor < ub {
+            new.push((BaseInterval::new(cursor, ub), value));
+        }
+
+        new.sort_by(|a, b| a.0.get_lb().partial_cmp(&b.0.get_lb()).unwrap());
+        self.entries = new;
+        self.coalesce();
+    }
+
+    // Join neighbouring entries that touch and have an equal value
+    fn coalesce(&mut self) {
+        let mut new: Vec<(BaseInterval<T>, V)> = Vec::new();
+        for (interval, value) in self.entries.drain(..) {
+            match new.last_mut() {
+                Some(last) if last.0.get_ub() == interval.get_lb() && last.1 == value => {
+                    last.0 = BaseInterval::new(last.0.get_lb(), interval.get_ub());
+                }
+                _ => new.push((interval, value)),
+            }
+        }
+        self.entries = new;
+    }
+
+    /// Value at `num`. On a border shared by two ranges, the value of the lower range is returned.
+    pub fn get(&self, num: T) -> Option<&V> {
+        let idx = self.entries.partition_point(|x| x.0.get_ub() < num);
+        match self.entries.get(idx) {
+            Some((interval, value)) if interval.contains(&num) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn contains_num(&self, num: T) -> bool {
+        self.get(num).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(BaseInterval<T>, V)> {
+        self.entries.iter()
+    }
+
+    /// Iterate over the ranges that overlap `[lb, ub]`, clipped to that range.
+    pub fn range(&self, lb: T, ub: T) -> impl Iterator<Item = (BaseInterval<T>, &V)> {
+        let (lb, ub) = BaseInterval::new(lb, ub).get_bounds();
+        self.entries
+            .iter()
+            .filter(move |x| x.0.get_ub() > lb && x.0.get_lb() < ub)
+            .map(move |(interval, value)| {
+                let (this_lb, this_ub) = interval.get_bounds();
+                let this_lb = if this_lb > lb { this_lb } else { lb };
+                let this_ub = if this_ub < ub { this_ub } else { ub };
+                (BaseInterval::new(this_lb, this_ub), value)
+            })
+    }
+
+    pub fn to_vec(&self) -> Vec<(BaseInterval<T>, V)> {
+        self.entries.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[derive(Clone, PartialEq, Debug)]
+    enum Flag {
+        Off,
+        On,
+    }
+
+    #[test]
+    fn test_insert_override() {
+        let mut map = IntervalMap::new();
+        map.insert(BaseInterval::new(0, 10), Flag::Off);
+        map.insert(BaseInterval::new(3, 5), Flag::On);
+        map.insert(BaseInterval::new(8, 12), Flag::On);
+        let that = vec![
+            (BaseInterval::new(0, 3), Flag::Off),
+            (BaseInterval::new(3, 5), Flag::On),
+            (BaseInterval::new(5, 8), Flag::Off),
+            (BaseInterval::new(8, 12), Flag::On),
+        ];
+        assert_eq!(map.to_vec(), that);
+    }
+
+    #[test]
+    fn test_insert_with_merge() {
+        let mut map = IntervalMap::new();
+        map.insert(BaseInterval::new(0, 4), vec!["a"]);
+        map.insert_with(BaseInterval::new(2, 6), vec!["b"], |old, new| {
+            old.iter().chain(new.iter()).cloned().collect()
+        });
+        let that = vec![
+            (BaseInterval::new(0, 2), vec!["a"]),
+            (BaseInterval::new(2, 4), vec!["a", "b"]),
+            (BaseInterval::new(4, 6), vec!["b"]),
+        ];
+        assert_eq!(map.to_vec(), that);
+    }
+
+    #[test]
+    fn test_coalesce() {
+        let mut map = IntervalMap::new();
+        map.insert(BaseInterval::new(0, 2), Flag::On);
+        map.insert(BaseInterval::new(4, 6), Flag::On);
+        assert_eq!(map.len(), 2);
+        map.insert(BaseInterval::new(2, 4), Flag::On);
+        assert_eq!(map.to_vec(), vec![(BaseInterval::new(0, 6), Flag::On)]);
+
+        let map = IntervalMap::from_vec(vec![
+            (BaseInterval::new(0, 3), Flag::Off),
+            (BaseInterval::new(3, 5), Flag::Off),
+        ]);
+        assert_eq!(map.to_vec(), vec![(BaseInterval::new(0, 5), Flag::Off)]);
+    }
+
+    #[test]
+    fn test_get() {
+        let map = IntervalMap::from_vec(vec![
+            (BaseInterval::new(0, 3), Flag::Off),
+            (BaseInterval::new(3, 5), Flag::On),
+            (BaseInterval::new(7, 9), Flag::Off),
+        ]);
+        assert_eq!(map.get(0), Some(&Flag::Off));
+        assert_eq!(map.get(3), Some(&Flag::Off));
+        assert_eq!(map.get(4), Some(&Flag::On));
+        assert_eq!(map.get(6), None);
+        assert_eq!(map.get(9), Some(&Flag::Off));
+        assert!(!map.contains_num(-1));
+    }
+
+    #[test]
+    fn test_range() {
+        let map = IntervalMap::from_vec(vec![
+            (BaseInterval::new(0, 3), Flag::Off),
+            (BaseInterval::new(3, 5), Flag::On),
+            (BaseInterval::new(7, 9), Flag::Off),
+        ]);
+        let this: Vec<(BaseInterval<i64>, &Flag)> = map.range(2, 8).collect();
+        let that = vec![
+            (BaseInterval::new(2, 3), &Flag::Off),
+            (BaseInterval::new(3, 5), &Flag::On),
+            (BaseInterval::new(7, 8), &Flag::Off),
+        ];
+        assert_eq!(this, that);
+        assert_eq!(map.iter().count(), 3);
+    }
+}
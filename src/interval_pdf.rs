@@ -0,0 +1,252 @@
+use crate::{Cumulative, IntervalCollection};
+use core::cmp::PartialOrd;
+use core::fmt;
+use core::fmt::{Display, Formatter};
+use num_traits::{Num, ToPrimitive};
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+/// Reasons why an IntervalCollection can not be normalized into an IntervalPdf.
+pub enum IntervalPdfError {
+    /// The collection has no intervals with a positive width and value, so it can not be scaled
+    /// to a total probability of one.
+    ZeroMass,
+    /// At least one interval has a negative value, which is not a valid density.
+    NegativeValue,
+    /// A bound or value could not be represented as an f64.
+    NotRepresentable,
+}
+
+impl Display for IntervalPdfError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IntervalPdfError::ZeroMass => write!(f, "collection has a total mass of zero"),
+            IntervalPdfError::NegativeValue => write!(f, "collection contains a negative value"),
+            IntervalPdfError::NotRepresentable => {
+                write!(
+                    f,
+                    "collection contains a number that does not fit in an f64"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IntervalPdfError {}
+
+#[derive(Clone, PartialEq, Debug)]
+/// Piecewise-uniform probability distribution, made by normalizing an IntervalCollection so that
+/// its total value (width × value, summed) is one. Densities and probabilities are given as f64.
+/// The cdf is the Cumulative of the collection, divided by its total.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{Interval, IntervalPdf, combine_intervals};
+///
+/// let collection = combine_intervals(vec![Interval::new(0, 1, 1), Interval::new(1, 3, 2)]);
+/// let pdf = IntervalPdf::new(&collection).unwrap();
+///
+/// assert_eq!(pdf.pdf(2), 0.4);
+/// assert_eq!(pdf.cdf(1), 0.2);
+/// assert_eq!(pdf.quantile(0.6), Some(2.0));
+/// assert!((pdf.mean() - 1.7).abs() < 1e-12);
+/// ```
+pub struct IntervalPdf<T> {
+    // Running integral of the intervals with a positive width and value
+    cumulative: Cumulative<T>,
+    total: f64,
+}
+
+impl<T> IntervalPdf<T>
+where
+    T: PartialOrd + Clone + Copy + ToPrimitive,
+{
+    /// Normalize `collection` into a distribution. Fails if any value is negative, or if there is
+    /// no probability mass to normalize.
+    pub fn new<U>(collection: &IntervalCollection<T, U>) -> Result<Self, IntervalPdfError>
+    where
+        U: Num + PartialOrd + Clone + Copy + ToPrimitive,
+    {
+        let intervals = collection.to_vec();
+        if intervals.iter().any(|x| x.get_value() < U::zero()) {
+            return Err(IntervalPdfError::NegativeValue);
+        }
+        let pieces = intervals
+            .into_iter()
+            .map(|x| x.to_tuple())
+            .filter(|(lb, ub, val)| lb < ub && *val > U::zero());
+        let cumulative =
+            Cumulative::from_pieces(pieces).ok_or(IntervalPdfError::NotRepresentable)?;
+        let total = cumulative.get_total();
+        if total <= 0.0 {
+            return Err(IntervalPdfError::ZeroMass);
+        }
+        Ok(IntervalPdf { cumulative, total })
+    }
+
+    pub fn len(&self) -> usize {
+        self.cumulative.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cumulative.is_empty()
+    }
+
+    pub fn get_bounds(&self) -> (T, T) {
+        self.cumulative.get_bounds().unwrap()
+    }
+
+    /// Density at `x`. On a border between two pieces, the density of the upper piece is used.
+    pub fn pdf(&self, x: T) -> f64 {
+        self.cumulative.get_slope(x) / self.total
+    }
+
+    /// Probability of a value of at most `x`.
+    pub fn cdf(&self, x: T) -> f64 {
+        self.cumulative.get_value(x) / self.total
+    }
+
+    /// Smallest value with a cumulative probability of `p`, or None if `p` is not in [0, 1]. For
+    /// `p` of zero this is the lowerbound of the distribution.
+    pub fn quantile(&self, p: f64) -> Option<f64> {
+        if !(0.0..=1.0).contains(&p) {
+            return None;
+        }
+        // Rounding can leave p × total just above the total, which is still reached at the end
+        let y = (p * self.total).min(self.cumulative.get_total());
+        self.cumulative.inverse(y)
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.cumulative
+            .iter_f64()
+            .map(|(lb, ub, val)| val * (ub - lb) * (lb + ub) / 2.0)
+            .sum::<f64>()
+            / self.total
+    }
+
+    pub fn variance(&self) -> f64 {
+        let second_moment = self
+            .cumulative
+            .iter_f64()
+            .map(|(lb, ub, val)| val * (ub - lb) * (lb * lb + lb * ub + ub * ub) / 3.0)
+            .sum::<f64>()
+            / self.total;
+        let mean = self.mean();
+        second_moment - mean * mean
+    }
+}
+
+#[cfg(feature = "rand")]
+impl<T> IntervalPdf<T>
+where
+    T: PartialOrd + Clone + Copy + ToPrimitive,
+{
+    /// Draw a value from the distribution, by looking up a uniform random probability in the cdf.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.quantile(rng.gen::<f64>()).unwrap()
+    }
+
+    /// Endless iterator of samples from the distribution.
+    pub fn sample_iter<'a, R: Rng + ?Sized>(
+        &'a self,
+        rng: &'a mut R,
+    ) -> impl Iterator<Item = f64> + 'a {
+        core::iter::repeat_with(move || self.sample(rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{combine_intervals, Interval};
+    use alloc::vec;
+
+    fn example() -> IntervalPdf<i64> {
+        let collection = combine_intervals(vec![
+            Interval::new(0, 1, 1),
+            Interval::new(1, 3, 2),
+            Interval::new(5, 6, 5),
+        ]);
+        IntervalPdf::new(&collection).unwrap()
+    }
+
+    #[test]
+    fn test_pdf_cdf() {
+        let pdf = example();
+        assert_eq!(pdf.len(), 3);
+        assert_eq!(pdf.pdf(0), 0.1);
+        assert_eq!(pdf.pdf(2), 0.2);
+        assert_eq!(pdf.pdf(4), 0.0);
+        assert_eq!(pdf.pdf(5), 0.5);
+        assert_eq!(pdf.cdf(-1), 0.0);
+        assert_eq!(pdf.cdf(1), 0.1);
+        assert_eq!(pdf.cdf(4), 0.5);
+        assert_eq!(pdf.cdf(6), 1.0);
+        assert_eq!(pdf.cdf(10), 1.0);
+    }
+
+    #[test]
+    fn test_quantile() {
+        let pdf = example();
+        assert_eq!(pdf.quantile(0.0), Some(0.0));
+        assert_eq!(pdf.quantile(0.1), Some(1.0));
+        assert_eq!(pdf.quantile(0.5), Some(3.0));
+        assert_eq!(pdf.quantile(0.75), Some(5.5));
+        assert_eq!(pdf.quantile(1.0), Some(6.0));
+        assert_eq!(pdf.quantile(1.5), None);
+    }
+
+    #[test]
+    fn test_leading_zero_values() {
+        // Pieces without probability are left out, so the quantile of zero is where mass starts
+        let collection = IntervalCollection::from_vec(vec![
+            Interval::new(0.0, 2.0, 0.0),
+            Interval::new(2.0, 4.0, 1.0),
+        ]);
+        let pdf = IntervalPdf::new(&collection).unwrap();
+        assert_eq!(pdf.get_bounds(), (2.0, 4.0));
+        assert_eq!(pdf.quantile(0.0), Some(2.0));
+        assert_eq!(pdf.quantile(1.0), Some(4.0));
+        assert_eq!(pdf.cdf(3.0), 0.5);
+    }
+
+    #[test]
+    fn test_moments() {
+        let collection = IntervalCollection::from_vec(vec![Interval::new(0.0, 2.0, 3.0)]);
+        let pdf = IntervalPdf::new(&collection).unwrap();
+        assert!((pdf.mean() - 1.0).abs() < 1e-12);
+        assert!((pdf.variance() - 1.0 / 3.0).abs() < 1e-12);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_sample() {
+        use alloc::vec::Vec;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let pdf = example();
+        let mut rng = StdRng::seed_from_u64(7);
+        let samples: Vec<f64> = pdf.sample_iter(&mut rng).take(10000).collect();
+        assert!(samples
+            .iter()
+            .all(|x| (0.0..=3.0).contains(x) || (5.0..=6.0).contains(x)));
+        let upper = samples.iter().filter(|x| **x >= 5.0).count() as f64 / 10000.0;
+        assert!((upper - 0.5).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_errors() {
+        let empty: IntervalCollection<i64, i64> = IntervalCollection::new();
+        assert_eq!(IntervalPdf::new(&empty), Err(IntervalPdfError::ZeroMass));
+        let negative = combine_intervals(vec![Interval::new(0, 2, -1)]);
+        assert_eq!(
+            IntervalPdf::new(&negative),
+            Err(IntervalPdfError::NegativeValue)
+        );
+    }
+}
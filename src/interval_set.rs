@@ -0,0 +1,496 @@
+use crate::{BaseInterval, Bound, IntervalLike};
+use alloc::vec::Vec;
+use core::cmp::PartialOrd;
+use core::fmt;
+use core::fmt::{Debug, Display, Formatter};
+use core::ops::Add;
+use num_traits::ToPrimitive;
+
+#[derive(Clone, Hash, Eq, PartialEq)]
+/// Set of points, stored as sorted BaseIntervals that neither overlap nor touch. Only ordering is
+/// needed from the bounds, so besides numbers and times this also works for strings (key ranges),
+/// tuples (version ranges) or any other ordered type. Only `measure` needs widths.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{BaseInterval, IntervalSet};
+///
+/// let keys = IntervalSet::from_vec(vec![
+///     BaseInterval::new("apple", "cherry"),
+///     BaseInterval::new("banana", "date"),
+///     BaseInterval::new("fig", "grape"),
+/// ]);
+///
+/// assert_eq!(keys.len(), 2);
+/// assert!(keys.contains(&"coconut"));
+/// assert!(!keys.contains(&"elderberry"));
+/// assert!(keys.overlaps(&BaseInterval::new("eggplant", "figs")));
+/// assert!(!keys.overlaps(&BaseInterval::new("eggplant", "fennel")));
+/// ```
+pub struct IntervalSet<T> {
+    intervals: Vec<BaseInterval<T>>,
+}
+
+impl<T> Debug for IntervalSet<T>
+where
+    T: PartialOrd + Clone + Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IntervalSet")
+            .field("intervals", &self.intervals)
+            .finish()
+    }
+}
+
+impl<T> Default for IntervalSet<T>
+where
+    T: PartialOrd + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> IntervalSet<T>
+where
+    T: PartialOrd + Clone,
+{
+    pub fn new() -> Self {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn from_vec(vec: Vec<BaseInterval<T>>) -> Self {
+        let mut set = IntervalSet { intervals: vec };
+        set.normalize();
+        set
+    }
+
+    /// Create a set out of anything interval-shaped, ignoring any values they might carry.
+    pub fn from_intervals<I: IntervalLike<T>>(intervals: &[I]) -> Self {
+        Self::from_vec(intervals.iter().map(|x| x.to_base()).collect())
+    }
+
+    // Sort on lowerbound and join everything that overlaps or touches
+    fn normalize(&mut self) {
+        let mut sorted: Vec<BaseInterval<T>> = self.intervals.drain(..).collect();
+        sorted.sort_by(|a, b| a.get_lb().partial_cmp(&b.get_lb()).unwrap());
+        for interval in sorted.into_iter() {
+            match self.intervals.last_mut() {
+                Some(last) if last.clone().can_join(interval.clone()) => {
+                    *last = last.clone().join(interval);
+                }
+                _ => self.intervals.push(interval),
+            }
+        }
+    }
+
+    pub fn insert(&mut self, interval: BaseInterval<T>) {
+        self.intervals.push(interval);
+        self.normalize();
+    }
+
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn get_bounds(&self) -> Option<(T, T)> {
+        match (self.intervals.first(), self.intervals.last()) {
+            (Some(first), Some(last)) => Some((first.get_lb(), last.get_ub())),
+            _ => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &BaseInterval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn to_vec(&self) -> Vec<BaseInterval<T>> {
+        self.intervals.clone()
+    }
+
+    // Index of the first interval that does not end before `num`
+    fn search(&self, num: &T) -> usize {
+        self.intervals.partition_point(|x| x.get_ub() < *num)
+    }
+
+    pub fn contains(&self, num: &T) -> bool {
+        match self.intervals.get(self.search(num)) {
+            Some(interval) => interval.contains(num),
+            None => false,
+        }
+    }
+
+    /// Whether all of `other` is inside this set. Since intervals in the set never touch, it has to
+    /// fit within a single one of them.
+    pub fn contains_interval<I: IntervalLike<T>>(&self, other: &I) -> bool {
+        match self.intervals.get(self.search(&other.get_lb())) {
+            Some(interval) => interval.superset(other),
+            None => false,
+        }
+    }
+
+    /// Whether `other` shares at least one point with this set.
+    pub fn overlaps<I: IntervalLike<T>>(&self, other: &I) -> bool {
+        match self.intervals.get(self.search(&other.get_lb())) {
+            Some(interval) => interval.overlaps(other),
+            None => false,
+        }
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut all = self.intervals.clone();
+        all.extend(other.intervals.iter().cloned());
+        Self::from_vec(all)
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut out = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a_lb, a_ub) = self.intervals[i].get_bounds();
+            let (b_lb, b_ub) = other.intervals[j].get_bounds();
+            let lb = if a_lb > b_lb { a_lb } else { b_lb };
+            let a_ends_first = a_ub <= b_ub;
+            let ub = if a_ends_first { a_ub } else { b_ub };
+            if lb <= ub {
+                out.push(BaseInterval::new(lb, ub));
+            }
+            if a_ends_first {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self::from_vec(out)
+    }
+}
+
+impl<T> IntervalSet<T>
+where
+    T: Bound,
+    T::Width: Default + Add<Output = T::Width>,
+{
+    /// Total width covered by the set.
+    pub fn measure(&self) -> T::Width {
+        // Folded from the zero width, as the Sum of IntFloat starts at one
+        self.intervals
+            .iter()
+            .fold(T::Width::default(), |acc, x| acc + x.get_width())
+    }
+}
+
+impl<T> IntervalSet<T>
+where
+    T: PartialOrd + Clone + ToPrimitive,
+{
+    // Bounds of all intervals as f64, or None if any of them can not be represented
+    fn to_f64_vec(&self) -> Option<Vec<(f64, f64)>> {
+        self.intervals.iter().map(|x| x.clone().to_f64()).collect()
+    }
+
+    // Total width of both sets and of their intersection, in a single merge over both
+    fn overlap_measures(&self, other: &IntervalSet<T>) -> Option<(f64, f64, f64)> {
+        let (a, b) = (self.to_f64_vec()?, other.to_f64_vec()?);
+        let measure_a = a.iter().map(|(lb, ub)| ub - lb).sum();
+        let measure_b = b.iter().map(|(lb, ub)| ub - lb).sum();
+        let mut shared = 0.0;
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            let lb = a[i].0.max(b[j].0);
+            let ub = a[i].1.min(b[j].1);
+            if lb < ub {
+                shared += ub - lb;
+            }
+            if a[i].1 <= b[j].1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Some((measure_a, measure_b, shared))
+    }
+
+    /// Width of the intersection divided by the width of the union. None if the union has a
+    /// width of zero, or a bound can not be represented as an f64.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intervalues::{BaseInterval, IntervalSet};
+    ///
+    /// let a = IntervalSet::from_vec(vec![BaseInterval::new(0, 4)]);
+    /// let b = IntervalSet::from_vec(vec![BaseInterval::new(2, 8)]);
+    /// assert_eq!(a.jaccard(&b), Some(0.25));
+    /// assert_eq!(a.overlap_coefficient(&b), Some(0.5));
+    /// ```
+    pub fn jaccard(&self, other: &IntervalSet<T>) -> Option<f64> {
+        let (measure_a, measure_b, shared) = self.overlap_measures(other)?;
+        let union = measure_a + measure_b - shared;
+        if union > 0.0 {
+            Some(shared / union)
+        } else {
+            None
+        }
+    }
+
+    /// Width of the intersection divided by the width of the smaller set, which is one whenever
+    /// one set is inside the other. None if either set has a width of zero, or a bound can not be
+    /// represented as an f64.
+    pub fn overlap_coefficient(&self, other: &IntervalSet<T>) -> Option<f64> {
+        let (measure_a, measure_b, shared) = self.overlap_measures(other)?;
+        let smallest = measure_a.min(measure_b);
+        if smallest > 0.0 {
+            Some(shared / smallest)
+        } else {
+            None
+        }
+    }
+
+    /// Largest distance from a point in either set to the nearest point in the other set. Zero
+    /// for two empty sets, and None if only one of them is empty or a bound can not be
+    /// represented as an f64.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intervalues::{BaseInterval, IntervalSet};
+    ///
+    /// let a = IntervalSet::from_vec(vec![BaseInterval::new(0, 10)]);
+    /// let b = IntervalSet::from_vec(vec![BaseInterval::new(0, 2), BaseInterval::new(8, 9)]);
+    /// // The middle of the gap in `b` is 3 away from it, and 10 is 1 away from 9
+    /// assert_eq!(a.hausdorff(&b), Some(3.0));
+    /// assert_eq!(b.hausdorff(&a), Some(3.0));
+    /// ```
+    pub fn hausdorff(&self, other: &IntervalSet<T>) -> Option<f64> {
+        let (a, b) = (self.to_f64_vec()?, other.to_f64_vec()?);
+        match (a.is_empty(), b.is_empty()) {
+            (true, true) => Some(0.0),
+            (false, false) => Some(directed_hausdorff(&a, &b).max(directed_hausdorff(&b, &a))),
+            _ => None,
+        }
+    }
+}
+
+// Largest distance from a point in `a` to the nearest point in `b`, both sorted and non-empty.
+// Within an interval of `a` this distance is largest at its bounds or at the middle of a gap in
+// `b` (clamped to the interval), so one pass over both is enough.
+fn directed_hausdorff(a: &[(f64, f64)], b: &[(f64, f64)]) -> f64 {
+    // Distance from `x` to `b`, where `j` is the first interval of `b` that does not end before `x`
+    let distance = |j: usize, x: f64| {
+        let above = b.get(j).map_or(f64::INFINITY, |y| (y.0 - x).max(0.0));
+        let below = if j > 0 { x - b[j - 1].1 } else { f64::INFINITY };
+        above.min(below)
+    };
+    let mut out: f64 = 0.0;
+    let mut j = 0;
+    for &(lb, ub) in a {
+        while j < b.len() && b[j].1 < lb {
+            j += 1;
+        }
+        out = out.max(distance(j, lb));
+        // Gaps between `b[k - 1]` and `b[k]` that start before `ub`, beginning with the gap that
+        // may contain `lb`. The rays before and after `b` are covered by the bounds.
+        let mut k = j.max(1);
+        while k < b.len() && b[k - 1].1 < ub {
+            let (start, end) = (b[k - 1].1, b[k].0);
+            let middle = ((start + end) / 2.0).clamp(lb, ub);
+            out = out.max((middle - start).min(end - middle));
+            k += 1;
+        }
+        while j < b.len() && b[j].1 < ub {
+            j += 1;
+        }
+        out = out.max(distance(j, ub));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{combine_as_set, Interval};
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn test_normalize() {
+        let this = IntervalSet::from_vec(vec![
+            BaseInterval::new(5, 6),
+            BaseInterval::new(0, 2),
+            BaseInterval::new(1, 3),
+            BaseInterval::new(3, 4),
+        ]);
+        let that = vec![BaseInterval::new(0, 4), BaseInterval::new(5, 6)];
+        assert_eq!(this.to_vec(), that);
+        assert_eq!(this.get_bounds(), Some((0, 6)));
+        assert_eq!(this.measure(), 5);
+        assert_eq!(IntervalSet::<i64>::new().measure(), 0);
+        assert_eq!(IntervalSet::<i64>::new().get_bounds(), None);
+    }
+
+    #[cfg(feature = "intfloat")]
+    #[test]
+    fn test_measure_intfloat() {
+        use intfloat::IntFloat;
+
+        let this = IntervalSet::from_vec(vec![BaseInterval::new(
+            IntFloat::from(0.0, 0),
+            IntFloat::from(2.0, 0),
+        )]);
+        assert_eq!(this.measure(), IntFloat::from(2.0, 0));
+    }
+
+    #[test]
+    fn test_contains_and_overlaps() {
+        let this = IntervalSet::from_vec(vec![BaseInterval::new(0, 4), BaseInterval::new(5, 6)]);
+        assert!(this.contains(&0));
+        assert!(this.contains(&4));
+        assert!(!this.contains(&-1));
+        assert!(!this.contains(&7));
+        assert!(this.contains_interval(&BaseInterval::new(1, 3)));
+        assert!(!this.contains_interval(&BaseInterval::new(3, 6)));
+        assert!(this.overlaps(&BaseInterval::new(4, 5)));
+        assert!(!this.overlaps(&BaseInterval::new(7, 8)));
+    }
+
+    #[test]
+    fn test_union_intersection() {
+        let a = IntervalSet::from_vec(vec![BaseInterval::new(0, 4), BaseInterval::new(6, 9)]);
+        let b = IntervalSet::from_vec(vec![BaseInterval::new(2, 7), BaseInterval::new(8, 10)]);
+        let union = IntervalSet::from_vec(vec![BaseInterval::new(0, 10)]);
+        let intersection = IntervalSet::from_vec(vec![
+            BaseInterval::new(2, 4),
+            BaseInterval::new(6, 7),
+            BaseInterval::new(8, 9),
+        ]);
+        assert_eq!(a.union(&b), union);
+        assert_eq!(a.intersection(&b), intersection);
+    }
+
+    #[test]
+    fn test_strings_and_versions() {
+        let mut keys = IntervalSet::new();
+        keys.insert(BaseInterval::new(
+            "user:100".to_string(),
+            "user:200".to_string(),
+        ));
+        keys.insert(BaseInterval::new(
+            "user:150".to_string(),
+            "user:300".to_string(),
+        ));
+        assert_eq!(keys.len(), 1);
+        assert!(keys.contains(&"user:250".to_string()));
+
+        let versions = IntervalSet::from_intervals(&[
+            Interval::new((1, 0), (1, 4), 1),
+            Interval::new((2, 0), (2, 3), 1),
+        ]);
+        assert!(versions.contains(&(1, 2)));
+        assert!(!versions.contains(&(1, 5)));
+    }
+
+    #[test]
+    fn test_matches_combine_as_set() {
+        let input = vec![
+            Interval::new(0, 2, 1),
+            Interval::new(1, 3, 2),
+            Interval::new(5, 7, 1),
+        ];
+        let this = IntervalSet::from_intervals(&input);
+        assert_eq!(this.to_vec(), combine_as_set(input));
+    }
+
+    #[test]
+    fn test_jaccard_and_overlap() {
+        let a = IntervalSet::from_vec(vec![BaseInterval::new(0, 4), BaseInterval::new(6, 9)]);
+        let b = IntervalSet::from_vec(vec![BaseInterval::new(2, 7), BaseInterval::new(8, 10)]);
+        // Intersection 2 + 1 + 1, union 10
+        assert_eq!(a.jaccard(&b), Some(0.4));
+        assert_eq!(a.overlap_coefficient(&b), Some(4.0 / 7.0));
+        assert_eq!(a.jaccard(&a), Some(1.0));
+        let inner = IntervalSet::from_vec(vec![BaseInterval::new(1, 2)]);
+        assert_eq!(a.overlap_coefficient(&inner), Some(1.0));
+        let empty = IntervalSet::new();
+        assert_eq!(a.jaccard(&empty), Some(0.0));
+        assert_eq!(a.overlap_coefficient(&empty), None);
+        assert_eq!(empty.jaccard(&empty), None);
+    }
+
+    #[test]
+    fn test_hausdorff() {
+        let a = IntervalSet::from_vec(vec![
+            BaseInterval::new(0.0, 1.0),
+            BaseInterval::new(9.0, 10.0),
+        ]);
+        let b = IntervalSet::from_vec(vec![BaseInterval::new(4.0, 5.0)]);
+        assert_eq!(a.hausdorff(&b), Some(5.0));
+        assert_eq!(a.hausdorff(&a), Some(0.0));
+        let c = IntervalSet::from_vec(vec![BaseInterval::new(-1.0, 11.0)]);
+        // The middle of the gap in `a` is 4 away from it
+        assert_eq!(a.hausdorff(&c), Some(4.0));
+        // Gaps that start before an interval but are widest inside it
+        let a = IntervalSet::from_vec(vec![BaseInterval::new(1, 1), BaseInterval::new(7, 16)]);
+        let b = IntervalSet::from_vec(vec![BaseInterval::new(2, 14)]);
+        assert_eq!(a.hausdorff(&b), Some(3.0));
+        let a = IntervalSet::from_vec(vec![BaseInterval::new(7, 13)]);
+        let b = IntervalSet::from_vec(vec![BaseInterval::new(3, 4), BaseInterval::new(17, 17)]);
+        assert_eq!(a.hausdorff(&b), Some(6.5));
+        let empty = IntervalSet::new();
+        assert_eq!(a.hausdorff(&empty), None);
+        assert_eq!(empty.hausdorff(&IntervalSet::<i64>::new()), Some(0.0));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_hausdorff_brute_force() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        // Seeded, so the cases are the same on every run
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut next = |max: i64| rng.gen_range(0..max);
+        let mut random_set = || {
+            let count = 1 + next(4);
+            let intervals = (0..count)
+                .map(|_| {
+                    let lb = next(20);
+                    BaseInterval::new(lb, lb + next(6))
+                })
+                .collect();
+            IntervalSet::from_vec(intervals)
+        };
+        // With integer bounds the largest distance is at a multiple of 0.5, so sampling all
+        // points in steps of 0.25 finds it exactly
+        let distance = |x: f64, set: &IntervalSet<i64>| {
+            set.iter()
+                .map(|y| {
+                    let (lb, ub) = (*y).to_f64().unwrap();
+                    (lb - x).max(x - ub).max(0.0)
+                })
+                .fold(f64::INFINITY, f64::min)
+        };
+        let directed = |from: &IntervalSet<i64>, to: &IntervalSet<i64>| {
+            let mut out: f64 = 0.0;
+            for interval in from.iter() {
+                let (lb, ub) = (*interval).to_f64().unwrap();
+                let steps = ((ub - lb) * 4.0) as usize;
+                for step in 0..=steps {
+                    out = out.max(distance(lb + step as f64 / 4.0, to));
+                }
+            }
+            out
+        };
+        for _ in 0..3000 {
+            let (a, b) = (random_set(), random_set());
+            let expected = directed(&a, &b).max(directed(&b, &a));
+            assert_eq!(a.hausdorff(&b), Some(expected), "{:?} {:?}", a, b);
+        }
+    }
+}
@@ -1,13 +1,55 @@
 //! # Intervalues
 //!
 //! `intervalues` brings functionality to combine valued intervals together in an efficient manner.
+//!
+//! The crate is `no_std` compatible and only needs `alloc` when the default `std` feature is
+//! disabled. Bounds on `std::time` types, the `intfloat` feature and the `random` module do need
+//! `std`.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
 
+mod allen_relation;
 mod base_interval;
+mod bound;
+mod box_collection;
+mod box_interval;
+mod circular_interval;
 mod combine_intervals;
+mod cumulative;
 mod interval;
 mod interval_collection;
+mod interval_index;
+mod interval_like;
+mod interval_map;
+mod interval_pdf;
+mod interval_set;
+mod piecewise_linear;
+#[cfg(feature = "random")]
+pub mod random;
+mod recurring_interval;
+mod resample_mode;
+mod value_scaling;
 
+pub use crate::allen_relation::AllenRelation;
 pub use crate::base_interval::BaseInterval;
-pub use crate::combine_intervals::{combine_as_set, combine_intervals};
+pub use crate::bound::{Bound, NumericWidth, Period, WidthMul};
+pub use crate::box_collection::{combine_boxes, BoxCollection};
+pub use crate::box_interval::BoxInterval;
+pub use crate::circular_interval::{CircularDomain, CircularInterval};
+pub use crate::combine_intervals::{combine_as_set, combine_intervals, combine_intervals_with_ids};
+pub use crate::cumulative::Cumulative;
 pub use crate::interval::Interval;
 pub use crate::interval_collection::IntervalCollection;
+pub use crate::interval_index::IntervalIndex;
+pub use crate::interval_like::{IntervalLike, ValuedIntervalLike};
+pub use crate::interval_map::IntervalMap;
+pub use crate::interval_pdf::{IntervalPdf, IntervalPdfError};
+pub use crate::interval_set::IntervalSet;
+pub use crate::piecewise_linear::PiecewiseLinear;
+pub use crate::recurring_interval::RecurringInterval;
+pub use crate::resample_mode::ResampleMode;
+pub use crate::value_scaling::ValueScaling;
@@ -1,86 +1,427 @@
-use intervalues;
-use intervalues::Interval;
+use clap::{Parser, Subcommand, ValueEnum};
+use intervalues::{
+    combine_as_set, combine_intervals, Bound, Interval, IntervalCollection, IntervalSet, WidthMul,
+};
 use intfloat::IntFloat;
-use num_traits::ToPrimitive;
-use rand::Rng;
+use num_traits::{Num, ToPrimitive};
 use rust_decimal::Decimal;
-use std::time::Instant;
-
-fn main() {
-    let mut rng = rand::thread_rng();
-
-    println!(
-        "Running the binary of >intervalues< will show a demo of what the library of it can do."
-    );
-    println!("What follows are variations of combining 1 000 000 intervals between 0 and 9:");
-
-    println!(
-        "\n(1) Valued and using i32 typed interval borders, value set to 1 for all. \
-    Converts to BaseInterval and returns IntervalCollection"
-    );
-    let mut input = Vec::new();
-    let n = 1000000;
-    for _ in 0..n {
-        input.push(Interval::new(rng.gen_range(0..10), rng.gen_range(0..10), 1));
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::io::Read;
+use std::ops::{AddAssign, SubAssign};
+use std::process::ExitCode;
+use std::str::FromStr;
+
+/// Combine valued intervals from the command line. Intervals are read as one `lb,ub[,value]` per
+/// line (value defaults to 1), from a file or from stdin. Empty lines and lines starting with `#`
+/// are skipped.
+#[derive(Parser, Debug)]
+#[command(name = "intervalues", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Numeric type of the bounds and values
+    #[arg(long = "type", value_enum, default_value_t = NumType::I64, global = true)]
+    num_type: NumType,
+
+    /// Number of decimals kept for the intfloat type
+    #[arg(long, default_value_t = 2, global = true)]
+    decimals: u32,
+
+    /// Format of the input
+    #[arg(long, value_enum, default_value_t = InputFormat::Csv, global = true)]
+    input_format: InputFormat,
+
+    /// Format of the output
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    output_format: OutputFormat,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Combine the intervals and write the resulting collection
+    Combine {
+        /// Input file, or stdin if left out or `-`
+        file: Option<String>,
+    },
+    /// Write the intervals covered by any input interval
+    Set { file: Option<String> },
+    /// Write the combined intervals as positive integer counts
+    Counter { file: Option<String> },
+    /// Write the combined value at the given points
+    Query {
+        file: Option<String>,
+        /// Points to look up, comma separated or repeated
+        #[arg(long = "at", value_delimiter = ',', required = true)]
+        points: Vec<String>,
+    },
+    /// Write summary statistics of the combined intervals
+    Stats { file: Option<String> },
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+enum NumType {
+    I64,
+    Decimal,
+    Intfloat,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+enum InputFormat {
+    /// Comma separated
+    Csv,
+    /// Tab separated
+    Tsv,
+    /// Separated by any whitespace
+    Ws,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+enum OutputFormat {
+    /// Intervals printed as `[lb;ub]x value`
+    Text,
+    Csv,
+    Tsv,
+    Json,
+}
+
+fn parse_intfloat(text: &str, decimals: u32) -> Result<IntFloat, String> {
+    let num = Decimal::from_str(text).map_err(|e| e.to_string())?;
+    let base = (num * Decimal::from(10i64.pow(decimals))).round();
+    match base.to_isize() {
+        Some(base) => Ok(IntFloat::new(base, decimals as isize)),
+        None => Err(format!("{} does not fit in an intfloat", text)),
+    }
+}
+
+fn read_input(file: &Option<String>) -> Result<String, String> {
+    let mut text = String::new();
+    match file.as_deref() {
+        None | Some("-") => std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| e.to_string())?,
+        Some(path) => {
+            text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+            0
+        }
+    };
+    Ok(text)
+}
+
+fn split_line(line: &str, format: InputFormat) -> Vec<&str> {
+    match format {
+        InputFormat::Csv => line.split(',').map(|x| x.trim()).collect(),
+        InputFormat::Tsv => line.split('\t').map(|x| x.trim()).collect(),
+        InputFormat::Ws => line.split_whitespace().collect(),
+    }
+}
+
+fn parse_intervals<N, F>(
+    text: &str,
+    format: InputFormat,
+    parse: F,
+) -> Result<Vec<Interval<N, N>>, String>
+where
+    N: Num + PartialOrd + Clone,
+    F: Fn(&str) -> Result<N, String>,
+{
+    let mut out = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields = split_line(line, format);
+        let err = |e: String| format!("line {}: {}", i + 1, e);
+        let (lb, ub, val) = match fields.as_slice() {
+            [lb, ub] => (parse(lb).map_err(err)?, parse(ub).map_err(err)?, N::one()),
+            [lb, ub, val] => (
+                parse(lb).map_err(err)?,
+                parse(ub).map_err(err)?,
+                parse(val).map_err(err)?,
+            ),
+            _ => {
+                return Err(err(format!(
+                    "expected 2 or 3 fields, found {}",
+                    fields.len()
+                )))
+            }
+        };
+        out.push(Interval::new(lb, ub, val));
+    }
+    Ok(out)
+}
+
+// Write rows with named columns in the requested format
+fn format_rows(header: &[&str], rows: &[Vec<String>], format: OutputFormat) -> String {
+    let mut out = String::new();
+    match format {
+        OutputFormat::Text => {
+            for row in rows {
+                match row.as_slice() {
+                    [lb, ub] => out.push_str(&format!("[{};{}]\n", lb, ub)),
+                    [lb, ub, val] => out.push_str(&format!("[{};{}]x{}\n", lb, ub, val)),
+                    _ => out.push_str(&format!("{}\n", row.join(" "))),
+                }
+            }
+        }
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let sep = if format == OutputFormat::Csv {
+                ","
+            } else {
+                "\t"
+            };
+            out.push_str(&format!("{}\n", header.join(sep)));
+            for row in rows {
+                out.push_str(&format!("{}\n", row.join(sep)));
+            }
+        }
+        OutputFormat::Json => {
+            let objects: Vec<String> = rows
+                .iter()
+                .map(|row| {
+                    let fields: Vec<String> = header
+                        .iter()
+                        .zip(row.iter())
+                        .map(|(key, val)| format!("\"{}\": {}", key, val))
+                        .collect();
+                    format!("{{{}}}", fields.join(", "))
+                })
+                .collect();
+            out.push_str(&format!("[{}]\n", objects.join(", ")));
+        }
+    }
+    out
+}
+
+fn run<N, F>(cli: &Cli, parse: F) -> Result<String, String>
+where
+    N: Num
+        + PartialOrd
+        + Clone
+        + Copy
+        + Hash
+        + Eq
+        + Default
+        + AddAssign
+        + SubAssign
+        + ToPrimitive
+        + std::iter::Sum
+        + Display
+        + Debug
+        + Bound<Width = N>
+        + WidthMul<N, Output = N>,
+    F: Fn(&str) -> Result<N, String>,
+{
+    let file = match &cli.command {
+        Command::Combine { file }
+        | Command::Set { file }
+        | Command::Counter { file }
+        | Command::Query { file, .. }
+        | Command::Stats { file } => file,
+    };
+    let input = parse_intervals(&read_input(file)?, cli.input_format, &parse)?;
+    let n_input = input.len();
+    let format = cli.output_format;
+
+    let to_row = |x: &Interval<N, N>| {
+        vec![
+            x.get_lb().to_string(),
+            x.get_ub().to_string(),
+            x.get_value().to_string(),
+        ]
+    };
+    let out = match &cli.command {
+        Command::Combine { .. } => {
+            let rows: Vec<Vec<String>> = combine_intervals(input)
+                .to_vec()
+                .iter()
+                .map(to_row)
+                .collect();
+            format_rows(&["lb", "ub", "value"], &rows, format)
+        }
+        Command::Set { .. } => {
+            let rows: Vec<Vec<String>> = combine_as_set(input)
+                .iter()
+                .map(|x| vec![x.get_lb().to_string(), x.get_ub().to_string()])
+                .collect();
+            format_rows(&["lb", "ub"], &rows, format)
+        }
+        Command::Counter { .. } => {
+            let rows: Vec<Vec<String>> = combine_intervals(input)
+                .to_vec_as_counter()
+                .iter()
+                .map(|x| {
+                    vec![
+                        x.get_lb().to_string(),
+                        x.get_ub().to_string(),
+                        x.get_value().to_string(),
+                    ]
+                })
+                .collect();
+            format_rows(&["lb", "ub", "count"], &rows, format)
+        }
+        Command::Query { points, .. } => {
+            let collection = combine_intervals(input);
+            let mut rows = Vec::new();
+            for point in points {
+                let num = parse(point)?;
+                rows.push(vec![num.to_string(), collection.get_value(num).to_string()]);
+            }
+            let text_rows: Vec<Vec<String>> = rows.iter().map(|x| vec![x.join(" ")]).collect();
+            match format {
+                OutputFormat::Text => format_rows(&[], &text_rows, format),
+                _ => format_rows(&["point", "value"], &rows, format),
+            }
+        }
+        Command::Stats { .. } => format_stats(n_input, &combine_intervals(input), format),
+    };
+    Ok(out)
+}
+
+fn format_stats<N>(
+    n_input: usize,
+    collection: &IntervalCollection<N, N>,
+    format: OutputFormat,
+) -> String
+where
+    N: Num
+        + PartialOrd
+        + Clone
+        + Copy
+        + Default
+        + Display
+        + Bound<Width = N>
+        + WidthMul<N, Output = N>,
+{
+    let intervals = collection.to_vec();
+    let mut stats = vec![
+        ("input_intervals", n_input.to_string()),
+        ("combined_intervals", intervals.len().to_string()),
+    ];
+    if !intervals.is_empty() {
+        let (lb, ub) = collection.get_bounds();
+        let values: Vec<N> = intervals.iter().map(|x| x.get_value()).collect();
+        let min = values
+            .iter()
+            .fold(values[0], |a, b| if *b < a { *b } else { a });
+        let max = values
+            .iter()
+            .fold(values[0], |a, b| if *b > a { *b } else { a });
+        let covered = IntervalSet::from_vec(intervals.iter().map(|x| x.to_base()).collect());
+        stats.push(("lb", lb.to_string()));
+        stats.push(("ub", ub.to_string()));
+        stats.push(("covered", covered.measure().to_string()));
+        stats.push(("min_value", min.to_string()));
+        stats.push(("max_value", max.to_string()));
+        stats.push(("total_value", collection.total_value().to_string()));
+    }
+    match format {
+        OutputFormat::Json => {
+            let fields: Vec<String> = stats
+                .iter()
+                .map(|(key, val)| format!("\"{}\": {}", key, val))
+                .collect();
+            format!("{{{}}}\n", fields.join(", "))
+        }
+        _ => {
+            let rows: Vec<Vec<String>> = stats
+                .into_iter()
+                .map(|(key, val)| vec![key.to_string(), val])
+                .collect();
+            let rows: Vec<Vec<String>> = match format {
+                OutputFormat::Text => rows.iter().map(|x| vec![x.join(": ")]).collect(),
+                _ => rows,
+            };
+            format_rows(&["stat", "value"], &rows, format)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.num_type {
+        NumType::I64 => run(&cli, |x| {
+            i64::from_str(x).map_err(|e| format!("{}: {}", x, e))
+        }),
+        NumType::Decimal => run(&cli, |x| {
+            Decimal::from_str(x).map_err(|e| format!("{}: {}", x, e))
+        }),
+        NumType::Intfloat => run(&cli, |x| parse_intfloat(x, cli.decimals)),
+    };
+    match result {
+        Ok(out) => {
+            print!("{}", out);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
     }
-    let before = Instant::now();
-    let hi = intervalues::combine_intervals(input);
-    let after = Instant::now();
-    println!("{:?} in {:?}", hi, after - before);
-
-    println!(
-        "\n(2) Valued and using i32 typed interval borders, value set to Decimal 1.5 for all. \
-    Converts to BaseInterval and returns IntervalCollection"
-    );
-    let mut input = Vec::new();
-    let n = 1000000;
-    for _ in 0..n {
-        input.push(Interval::new(
-            rng.gen_range(0..10),
-            rng.gen_range(0..10),
-            // 1),
-            Decimal::from_f32_retain(1.5).unwrap(),
-        ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_i64(x: &str) -> Result<i64, String> {
+        i64::from_str(x).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_parse_intervals() {
+        let text = "# lb,ub,value\n0,2,1\n\n1, 3\n";
+        let this = parse_intervals(text, InputFormat::Csv, parse_i64).unwrap();
+        assert_eq!(this, vec![Interval::new(0, 2, 1), Interval::new(1, 3, 1)]);
+
+        let this = parse_intervals("0 2\t5", InputFormat::Ws, parse_i64).unwrap();
+        assert_eq!(this, vec![Interval::new(0, 2, 5)]);
+
+        let err = parse_intervals("0,2\n1,x,3", InputFormat::Csv, parse_i64).unwrap_err();
+        assert!(err.starts_with("line 2"));
+        assert!(parse_intervals("0", InputFormat::Csv, parse_i64).is_err());
+    }
+
+    #[test]
+    fn test_parse_intfloat() {
+        assert_eq!(parse_intfloat("1.25", 2), Ok(IntFloat::new(125, 2)));
+        assert_eq!(parse_intfloat("3", 1), Ok(IntFloat::new(30, 1)));
+        assert!(parse_intfloat("abc", 2).is_err());
     }
-    let before = Instant::now();
-    let hi = intervalues::combine_intervals(input);
-    let after = Instant::now();
-    println!("{:?} in {:?}", hi, after - before);
-
-    println!(
-        "\n(3) Valued and using Decimal (via float) typed interval borders, value set to Decimal(1.5) for all. \
-    Converts to BaseInterval and returns IntervalCollection"
-    );
-    let mut input = Vec::new();
-    let n = 1000000;
-    for _ in 0..n {
-        input.push(Interval::new(
-            Decimal::from_f32_retain(0.5 + rng.gen_range(0..10).to_f32().unwrap()).unwrap(),
-            Decimal::from_f32_retain(0.5 + rng.gen_range(0..10).to_f32().unwrap()).unwrap(),
-            Decimal::from_f32_retain(1.5).unwrap(),
-        ));
+
+    #[test]
+    fn test_format_rows() {
+        let rows = vec![vec!["0".to_string(), "1".to_string(), "2".to_string()]];
+        let header = ["lb", "ub", "value"];
+        assert_eq!(format_rows(&header, &rows, OutputFormat::Text), "[0;1]x2\n");
+        assert_eq!(
+            format_rows(&header, &rows, OutputFormat::Csv),
+            "lb,ub,value\n0,1,2\n"
+        );
+        assert_eq!(
+            format_rows(&header, &rows, OutputFormat::Json),
+            "[{\"lb\": 0, \"ub\": 1, \"value\": 2}]\n"
+        );
     }
-    let before = Instant::now();
-    let hi = intervalues::combine_intervals(input);
-    let after = Instant::now();
-    println!("{:?} in {:?}", hi, after - before);
-
-    println!(
-        "\n(4) Valued and using IntFloat typed interval borders, value set to IntFloat(1.5) for all. \
-    Converts to BaseInterval and returns IntervalCollection"
-    );
-    let mut input = Vec::new();
-    let n = 1000000;
-    for _ in 0..n {
-        input.push(Interval::new(
-            IntFloat::from(0.5 + rng.gen_range(0..10).to_f32().unwrap(), 1),
-            IntFloat::from(0.5 + rng.gen_range(0..10).to_f32().unwrap(), 1),
-            IntFloat::from(1.5, 1),
-        ));
+
+    #[test]
+    fn test_stats() {
+        let collection = combine_intervals(vec![Interval::new(0, 2, 1), Interval::new(1, 3, 2)]);
+        let this = format_stats(2, &collection, OutputFormat::Json);
+        assert!(this.contains("\"combined_intervals\": 3"));
+        assert!(this.contains("\"covered\": 3"));
+        assert!(this.contains("\"max_value\": 3"));
+        assert!(this.contains("\"total_value\": 6"));
+
+        let collection = combine_intervals(vec![Interval::new(
+            IntFloat::from(0.0, 0),
+            IntFloat::from(2.0, 0),
+            IntFloat::from(1.0, 0),
+        )]);
+        let this = format_stats(1, &collection, OutputFormat::Json);
+        assert!(this.contains("\"covered\": 2"));
+        assert!(this.contains("\"total_value\": 2"));
     }
-    let before = Instant::now();
-    let hi = intervalues::combine_intervals(input);
-    let after = Instant::now();
-    println!("{:?} in {:?}", hi, after - before);
-    println!("{} in {:?}", hi, after - before);
 }
@@ -0,0 +1,92 @@
+use alloc::vec::Vec;
+
+#[derive(Clone, PartialEq, Debug)]
+/// Continuous function that is linear between its corner points, and constant before the first
+/// and after the last corner. This is the exact result of integrating a step function, such as
+/// the rolling sum of an IntervalCollection.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::PiecewiseLinear;
+///
+/// let this = PiecewiseLinear::new(vec![(0.0, 0.0), (2.0, 4.0), (3.0, 4.0)]);
+/// assert_eq!(this.get_value(-1.0), 0.0);
+/// assert_eq!(this.get_value(0.5), 1.0);
+/// assert_eq!(this.get_value(2.5), 4.0);
+/// assert_eq!(this.get_value(10.0), 4.0);
+/// ```
+pub struct PiecewiseLinear {
+    points: Vec<(f64, f64)>,
+}
+
+impl PiecewiseLinear {
+    /// Function through the given corner points, which are sorted by their first element.
+    pub fn new(mut points: Vec<(f64, f64)>) -> Self {
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        PiecewiseLinear { points }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn get_points(&self) -> Vec<(f64, f64)> {
+        self.points.clone()
+    }
+
+    /// Value at `x`, interpolated between the corners around it. Zero if there are no corners.
+    pub fn get_value(&self, x: f64) -> f64 {
+        let idx = self.points.partition_point(|point| point.0 <= x);
+        if idx == 0 {
+            return self.points.first().map_or(0.0, |point| point.1);
+        }
+        let (x0, y0) = self.points[idx - 1];
+        match self.points.get(idx) {
+            Some(&(x1, y1)) => y0 + (y1 - y0) * (x - x0) / (x1 - x0),
+            None => y0,
+        }
+    }
+
+    /// The function with every value passed through `func`, which should be linear to keep the
+    /// result exact, such as scaling by a constant.
+    pub fn map_values<F: Fn(f64) -> f64>(&self, func: F) -> Self {
+        let points = self.points.iter().map(|(x, y)| (*x, func(*y))).collect();
+        PiecewiseLinear { points }
+    }
+
+    /// The function moved by `delta` along the x-axis.
+    pub fn shift(&self, delta: f64) -> Self {
+        let points = self.points.iter().map(|(x, y)| (x + delta, *y)).collect();
+        PiecewiseLinear { points }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_get_value() {
+        let this = PiecewiseLinear::new(vec![(4.0, 0.0), (0.0, 2.0), (2.0, 6.0)]);
+        assert_eq!(this.len(), 3);
+        assert_eq!(this.get_value(-5.0), 2.0);
+        assert_eq!(this.get_value(1.0), 4.0);
+        assert_eq!(this.get_value(2.0), 6.0);
+        assert_eq!(this.get_value(3.0), 3.0);
+        assert_eq!(this.get_value(5.0), 0.0);
+        assert_eq!(PiecewiseLinear::new(vec![]).get_value(1.0), 0.0);
+    }
+
+    #[test]
+    fn test_map_and_shift() {
+        let this = PiecewiseLinear::new(vec![(0.0, 0.0), (2.0, 4.0)]);
+        assert_eq!(this.map_values(|y| y / 2.0).get_value(1.0), 1.0);
+        assert_eq!(this.shift(-1.0).get_points(), vec![(-1.0, 0.0), (1.0, 4.0)]);
+    }
+}
@@ -0,0 +1,255 @@
+//! Generators of random intervals, for tests and benchmarks. Every generator is driven by a seed,
+//! so the same seed always gives the same intervals (for a given version of `rand`). Numbers are
+//! drawn as f64 and then converted to the bound and value types, which truncates them for integer
+//! types.
+//!
+//! # Examples
+//!
+//! ```
+//! use intervalues::random::{IntervalGenerator, Starts, Values, Widths};
+//! use intervalues::Interval;
+//!
+//! let generator = IntervalGenerator::new(
+//!     Starts::Poisson { start: 0.0, rate: 2.0 },
+//!     Widths::Pareto { scale: 1.0, shape: 1.5 },
+//!     Values::Fixed(1.0),
+//! );
+//! let input: Vec<Interval<i64, i64>> = generator.generate(1000, 42);
+//! assert_eq!(input.len(), 1000);
+//! assert_eq!(input, generator.generate(1000, 42));
+//! ```
+
+use crate::Interval;
+use alloc::vec::Vec;
+use core::cmp::PartialOrd;
+use num_traits::{Num, NumCast};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+/// Where intervals start.
+pub enum Starts {
+    /// Uniformly between `lb` and `ub`.
+    Uniform { lb: f64, ub: f64 },
+    /// Arrivals of a Poisson process from `start` on, with on average `rate` arrivals per unit.
+    /// Starts are increasing, like the arrival times of requests or events.
+    Poisson { start: f64, rate: f64 },
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+/// How wide intervals are.
+pub enum Widths {
+    Fixed(f64),
+    /// Uniformly between `min` and `max`.
+    Uniform {
+        min: f64,
+        max: f64,
+    },
+    /// Exponentially distributed with the given mean.
+    Exponential {
+        mean: f64,
+    },
+    /// Heavy-tailed: Pareto distributed with minimum `scale`. The lower `shape`, the heavier the
+    /// tail; for a shape of 1 or less the mean is infinite.
+    Pareto {
+        scale: f64,
+        shape: f64,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+/// Which values intervals carry.
+pub enum Values {
+    Fixed(f64),
+    /// Uniformly between `min` and `max`.
+    Uniform {
+        min: f64,
+        max: f64,
+    },
+    /// Exponentially distributed with the given mean.
+    Exponential {
+        mean: f64,
+    },
+}
+
+// Exponentially distributed number, by inverting the cdf at a uniform random probability
+fn exponential<R: Rng>(rng: &mut R, mean: f64) -> f64 {
+    -mean * (1.0 - rng.gen::<f64>()).ln()
+}
+
+fn uniform<R: Rng>(rng: &mut R, lb: f64, ub: f64) -> f64 {
+    if lb < ub {
+        rng.gen_range(lb..ub)
+    } else {
+        lb
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+/// Generator of valued intervals, with a distribution for their starts, widths and values.
+pub struct IntervalGenerator {
+    starts: Starts,
+    widths: Widths,
+    values: Values,
+}
+
+impl IntervalGenerator {
+    pub fn new(starts: Starts, widths: Widths, values: Values) -> Self {
+        IntervalGenerator {
+            starts,
+            widths,
+            values,
+        }
+    }
+
+    /// `n` random intervals as f64, the same for every call with the same `seed`.
+    pub fn generate_f64(&self, n: usize, seed: u64) -> Vec<(f64, f64, f64)> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut arrival = match self.starts {
+            Starts::Poisson { start, .. } => start,
+            Starts::Uniform { lb, .. } => lb,
+        };
+        let mut out = Vec::with_capacity(n);
+        for _ in 0..n {
+            let lb = match self.starts {
+                Starts::Uniform { lb, ub } => uniform(&mut rng, lb, ub),
+                Starts::Poisson { rate, .. } => {
+                    arrival += exponential(&mut rng, 1.0 / rate);
+                    arrival
+                }
+            };
+            let width = match self.widths {
+                Widths::Fixed(width) => width,
+                Widths::Uniform { min, max } => uniform(&mut rng, min, max),
+                Widths::Exponential { mean } => exponential(&mut rng, mean),
+                Widths::Pareto { scale, shape } => {
+                    scale / (1.0 - rng.gen::<f64>()).powf(1.0 / shape)
+                }
+            };
+            let value = match self.values {
+                Values::Fixed(value) => value,
+                Values::Uniform { min, max } => uniform(&mut rng, min, max),
+                Values::Exponential { mean } => exponential(&mut rng, mean),
+            };
+            out.push((lb, lb + width, value));
+        }
+        out
+    }
+
+    /// `n` random intervals, converting bounds and values with the given functions. Useful for
+    /// types that can not be cast from an f64 directly.
+    pub fn generate_with<T, U, F, G>(
+        &self,
+        n: usize,
+        seed: u64,
+        to_bound: F,
+        to_value: G,
+    ) -> Vec<Interval<T, U>>
+    where
+        T: PartialOrd + Clone,
+        U: Num + PartialOrd,
+        F: Fn(f64) -> T,
+        G: Fn(f64) -> U,
+    {
+        self.generate_f64(n, seed)
+            .into_iter()
+            .map(|(lb, ub, value)| Interval::new(to_bound(lb), to_bound(ub), to_value(value)))
+            .collect()
+    }
+
+    /// `n` random intervals, the same for every call with the same `seed`. Panics if a number does
+    /// not fit in the bound or value type.
+    pub fn generate<T, U>(&self, n: usize, seed: u64) -> Vec<Interval<T, U>>
+    where
+        T: PartialOrd + Clone + NumCast,
+        U: Num + PartialOrd + NumCast,
+    {
+        self.generate_with(n, seed, cast, cast)
+    }
+}
+
+fn cast<T: NumCast>(num: f64) -> T {
+    T::from(num).expect("generated number does not fit in the requested type")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reproducible() {
+        let generator = IntervalGenerator::new(
+            Starts::Uniform { lb: 0.0, ub: 100.0 },
+            Widths::Exponential { mean: 5.0 },
+            Values::Uniform { min: 1.0, max: 3.0 },
+        );
+        let this: Vec<Interval<f64, f64>> = generator.generate(100, 1);
+        assert_eq!(this, generator.generate(100, 1));
+        assert_ne!(this, generator.generate(100, 2));
+        assert!(this.iter().all(|x| x.get_lb() >= 0.0 && x.get_lb() < 100.0));
+        assert!(this
+            .iter()
+            .all(|x| x.get_value() >= 1.0 && x.get_value() < 3.0));
+    }
+
+    #[test]
+    fn test_poisson_starts() {
+        let generator = IntervalGenerator::new(
+            Starts::Poisson {
+                start: 10.0,
+                rate: 4.0,
+            },
+            Widths::Fixed(1.0),
+            Values::Fixed(2.0),
+        );
+        let this = generator.generate_f64(4000, 5);
+        assert!(this.windows(2).all(|x| x[0].0 <= x[1].0));
+        assert!(this[0].0 >= 10.0);
+        // 4000 arrivals at a rate of 4 take about 1000 units
+        let span = this.last().unwrap().0 - 10.0;
+        assert!((span - 1000.0).abs() < 100.0);
+        assert!(this
+            .iter()
+            .all(|x| (x.1 - x.0 - 1.0).abs() < 1e-9 && x.2 == 2.0));
+    }
+
+    #[test]
+    fn test_heavy_tail() {
+        let generator = IntervalGenerator::new(
+            Starts::Uniform { lb: 0.0, ub: 1.0 },
+            Widths::Pareto {
+                scale: 1.0,
+                shape: 1.2,
+            },
+            Values::Fixed(1.0),
+        );
+        let widths: Vec<f64> = generator
+            .generate_f64(10000, 9)
+            .iter()
+            .map(|x| x.1 - x.0)
+            .collect();
+        assert!(widths.iter().all(|x| *x >= 1.0));
+        assert!(widths.iter().any(|x| *x > 100.0));
+    }
+
+    #[test]
+    fn test_types() {
+        let generator = IntervalGenerator::new(
+            Starts::Uniform { lb: 0.0, ub: 10.0 },
+            Widths::Uniform { min: 0.0, max: 5.0 },
+            Values::Fixed(1.5),
+        );
+        let this: Vec<Interval<i32, f64>> = generator.generate(50, 3);
+        assert!(this.iter().all(|x| x.get_lb() >= 0 && x.get_ub() < 15));
+        #[cfg(feature = "decimal")]
+        {
+            let that = generator.generate_with(
+                50,
+                3,
+                |x| x as i32,
+                |x| rust_decimal::Decimal::from_f64_retain(x).unwrap(),
+            );
+            assert_eq!(this[0].get_bounds(), that[0].get_bounds());
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use crate::{combine_intervals, BaseInterval, Bound, Interval, IntervalCollection, Period};
+use alloc::vec::Vec;
+use core::cmp::PartialOrd;
+use core::fmt::{Debug, Display};
+use core::hash::Hash;
+use core::ops::{AddAssign, SubAssign};
+use num_traits::{Num, ToPrimitive};
+
+#[derive(Clone)]
+/// Interval that repeats itself every `period`, either a fixed number of times or until an end
+/// bound. Occurrences are generated lazily, so only the part that is actually used is expanded.
+/// Exclusions skip every occurrence that overlaps them (holidays, maintenance windows), while
+/// still counting towards the number of repetitions.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{BaseInterval, Interval, RecurringInterval, combine_intervals};
+///
+/// // Working hours on every day of a 30-day month, except for day 3
+/// let work = RecurringInterval::until(Interval::new(8, 17, 1), 24, 720)
+///     .exclude(BaseInterval::new(72, 96));
+///
+/// assert_eq!(work.iter().count(), 29);
+/// assert_eq!(work.iter().nth(3), Some(Interval::new(104, 113, 1)));
+/// assert_eq!(work.get_value(34), 1);
+/// assert_eq!(work.get_value(80), 0);
+///
+/// let out = combine_intervals(work.iter().collect());
+/// assert_eq!(out.total_value(), 29 * 9);
+/// ```
+pub struct RecurringInterval<T: Bound, U> {
+    base: Interval<T, U>,
+    period: T::Width,
+    count: Option<usize>,
+    end: Option<T>,
+    exclusions: Vec<BaseInterval<T>>,
+}
+
+impl<T, U> RecurringInterval<T, U>
+where
+    T: Bound + Copy,
+    T::Width: Period,
+    U: Num + PartialOrd + Clone + Copy,
+{
+    /// Repeat `base` every `period`, `count` times in total.
+    pub fn with_count(base: Interval<T, U>, period: T::Width, count: usize) -> Self {
+        RecurringInterval {
+            base,
+            period,
+            count: Some(count),
+            end: None,
+            exclusions: Vec::new(),
+        }
+    }
+
+    /// Repeat `base` every `period`, for as long as occurrences end no later than `end`.
+    pub fn until(base: Interval<T, U>, period: T::Width, end: T) -> Self {
+        RecurringInterval {
+            base,
+            period,
+            count: None,
+            end: Some(end),
+            exclusions: Vec::new(),
+        }
+    }
+
+    /// Skip all occurrences that overlap `exclusion`. Only touching it is not enough.
+    pub fn exclude(mut self, exclusion: BaseInterval<T>) -> Self {
+        self.exclusions.push(exclusion);
+        self
+    }
+
+    pub fn get_base(&self) -> Interval<T, U> {
+        self.base
+    }
+
+    fn is_excluded(&self, lb: &T, ub: &T) -> bool {
+        self.exclusions.iter().any(|x| {
+            let (ex_lb, ex_ub) = x.get_bounds();
+            *lb < ex_ub && ex_lb < *ub
+        })
+    }
+
+    // Number of occurrences before any limit by `end`. A period that does not move forward would
+    // repeat the same occurrence forever, so it only gives the first one.
+    fn get_count(&self) -> usize {
+        let lb = self.base.get_lb();
+        match self.count {
+            Some(count) if lb.offset(&self.period) <= lb => count.min(1),
+            Some(count) => count,
+            None if lb.offset(&self.period) <= lb => 1,
+            None => usize::MAX,
+        }
+    }
+
+    // Occurrences from the one at `first` onwards, each computed from the base directly
+    fn iter_from(&self, first: usize) -> impl Iterator<Item = Interval<T, U>> + '_ {
+        let (base_lb, base_ub) = self.base.get_bounds();
+        (first..self.get_count())
+            .map(move |i| {
+                let shift = self.period.repeat(i);
+                (base_lb.offset(&shift), base_ub.offset(&shift))
+            })
+            .take_while(move |(_, ub)| match &self.end {
+                Some(end) => ub <= end,
+                None => true,
+            })
+            .filter(move |(lb, ub)| !self.is_excluded(lb, ub))
+            .map(move |(lb, ub)| Interval::new(lb, ub, self.base.get_value()))
+    }
+
+    /// All occurrences in order, generated lazily.
+    pub fn iter(&self) -> impl Iterator<Item = Interval<T, U>> + '_ {
+        self.iter_from(0)
+    }
+
+    /// Occurrences that overlap `[lb, ub]`. The first overlapping occurrence is computed directly
+    /// from the period, so occurrences before `lb` are not expanded.
+    pub fn overlapping(&self, lb: T, ub: T) -> impl Iterator<Item = Interval<T, U>> + '_ {
+        let (lb, ub) = BaseInterval::new(lb, ub).get_bounds();
+        let base_ub = self.base.get_ub();
+        // Occurrences before this one end before lb, while this one may still end just before it
+        let first = if self.get_count() > 1 && base_ub < lb {
+            self.period.periods_in(&Bound::width(&base_ub, &lb))
+        } else {
+            0
+        };
+        self.iter_from(first)
+            .skip_while(move |x| x.get_ub() < lb)
+            .take_while(move |x| x.get_lb() <= ub)
+    }
+
+    /// Summed value of all occurrences that contain `num`.
+    pub fn get_value(&self, num: T) -> U {
+        self.overlapping(num, num)
+            .fold(U::zero(), |acc, x| acc + x.get_value())
+    }
+}
+
+impl<T, U> RecurringInterval<T, U>
+where
+    T: Bound + Copy + Hash + Eq,
+    T::Width: Period,
+    U: Num
+        + PartialOrd
+        + Default
+        + AddAssign
+        + SubAssign
+        + Clone
+        + Copy
+        + ToPrimitive
+        + core::iter::Sum
+        + Display
+        + Debug,
+{
+    /// Combine the occurrences with `collection`, only expanding the occurrences that overlap the
+    /// bounds of the collection. Everything outside of those bounds is left out, so occurrences
+    /// that cross one of the bounds are clipped to it.
+    pub fn combine_within(
+        &self,
+        collection: &IntervalCollection<T, U>,
+    ) -> IntervalCollection<T, U> {
+        if collection.is_empty() {
+            return collection.clone();
+        }
+        let (lb, ub) = collection.get_bounds();
+        let mut input: Vec<Interval<T, U>> = self
+            .overlapping(lb, ub)
+            .map(|x| {
+                let (x_lb, x_ub, val) = x.to_tuple();
+                let x_lb = if x_lb < lb { lb } else { x_lb };
+                let x_ub = if x_ub > ub { ub } else { x_ub };
+                Interval::new(x_lb, x_ub, val)
+            })
+            .collect();
+        input.extend(collection.to_vec());
+        combine_intervals(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_count() {
+        let this = RecurringInterval::with_count(Interval::new(0, 2, 3), 5, 4);
+        let that = vec![
+            Interval::new(0, 2, 3),
+            Interval::new(5, 7, 3),
+            Interval::new(10, 12, 3),
+            Interval::new(15, 17, 3),
+        ];
+        assert_eq!(this.iter().collect::<Vec<_>>(), that);
+
+        // Excluded occurrences still count
+        let this = this.exclude(BaseInterval::new(6, 6));
+        assert_eq!(this.iter().count(), 3);
+        assert_eq!(this.get_value(6), 0);
+    }
+
+    #[test]
+    fn test_until_and_overlapping() {
+        let this = RecurringInterval::until(Interval::new(8, 17, 1), 24, 720);
+        assert_eq!(this.iter().count(), 30);
+        assert_eq!(this.iter().last(), Some(Interval::new(704, 713, 1)));
+        let window: Vec<Interval<i64, i64>> = this.overlapping(100, 140).collect();
+        assert_eq!(
+            window,
+            vec![Interval::new(104, 113, 1), Interval::new(128, 137, 1)]
+        );
+
+        // Exclusions that only touch an occurrence keep it
+        let this = this.exclude(BaseInterval::new(17, 32));
+        assert_eq!(this.iter().count(), 30);
+    }
+
+    #[test]
+    fn test_overlapping_occurrences() {
+        let this = RecurringInterval::with_count(Interval::new(0.0, 3.0, 1.0), 2.0, 3);
+        assert_eq!(this.get_value(2.5), 2.0);
+        assert_eq!(this.get_value(7.5), 0.0);
+
+        // A period that does not move forward ends the recurrence
+        let this = RecurringInterval::until(Interval::new(0, 1, 1), 0, 10);
+        assert_eq!(this.iter().count(), 1);
+    }
+
+    #[test]
+    fn test_combine_within() {
+        // A schedule without an end, evaluated against a single week of bookings
+        let daily = RecurringInterval::with_count(Interval::new(8, 17, 1), 24, usize::MAX);
+        let bookings = combine_intervals(vec![
+            Interval::new(24 * 1000, 24 * 1000 + 10, 2),
+            Interval::new(24 * 1006, 24 * 1007, 2),
+        ]);
+        let out = daily.combine_within(&bookings);
+        assert_eq!(out.get_value(24 * 1000 + 9), 3);
+        assert_eq!(out.get_value(24 * 1003 + 9), 1);
+        assert_eq!(out.get_value(24 * 1006 + 20), 2);
+        assert_eq!(out.get_value(24 * 1008 + 9), 0);
+
+        // Occurrences crossing the bounds of the collection are clipped to them
+        let bookings = combine_intervals(vec![Interval::new(24 * 1000 + 12, 24 * 1001 + 10, 2)]);
+        let out = daily.combine_within(&bookings);
+        assert_eq!(out.get_bounds(), bookings.get_bounds());
+        assert_eq!(out.total_value(), 2 * 22 + 5 + 2);
+    }
+
+    #[test]
+    fn test_overlapping_far_away() {
+        // Only the occurrences in the window are expanded, not the billions before it
+        let daily = RecurringInterval::with_count(Interval::new(8, 17, 1), 24, usize::MAX);
+        let lb = 24 * 1_000_000_000_000i64;
+        let window: Vec<Interval<i64, i64>> = daily.overlapping(lb + 17, lb + 32).collect();
+        assert_eq!(
+            window,
+            vec![
+                Interval::new(lb + 8, lb + 17, 1),
+                Interval::new(lb + 32, lb + 41, 1)
+            ]
+        );
+        assert_eq!(daily.get_value(lb + 24 + 16), 1);
+        assert_eq!(daily.get_value(lb + 24 + 18), 0);
+
+        let this = RecurringInterval::with_count(Interval::new(0.5, 1.0, 1.0), 0.25, 10);
+        let window: Vec<Interval<f64, f64>> = this.overlapping(1.6, 1.7).collect();
+        assert_eq!(window.len(), 2);
+        assert_eq!(window[0].get_lb(), 1.25);
+        assert_eq!(this.overlapping(2.0, 3.0).count(), 6);
+        assert_eq!(this.overlapping(3.0, 4.0).count(), 2);
+        assert_eq!(this.overlapping(3.5, 4.0).count(), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_time_period() {
+        use std::time::{Duration, Instant};
+
+        let start = Instant::now();
+        let hour = Duration::from_secs(3600);
+        let base = Interval::new(start, start + hour, 1.0);
+        let this = RecurringInterval::with_count(base, 24 * hour, 7);
+        assert_eq!(this.iter().count(), 7);
+        assert_eq!(this.get_value(start + 48 * hour), 1.0);
+        assert_eq!(
+            this.overlapping(start + 49 * hour, start + 100 * hour)
+                .count(),
+            3
+        );
+    }
+}
@@ -0,0 +1,25 @@
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+/// How the pieces of an IntervalCollection within a bin are summarized into a single value when
+/// resampling. Parts of a bin that are not covered by any piece count as a value of zero.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{Interval, IntervalCollection, ResampleMode};
+///
+/// let this = IntervalCollection::from_vec(vec![Interval::new(0, 1, 4), Interval::new(1, 4, 2)]);
+/// assert_eq!(this.resample(0, 4, 2, ResampleMode::Integral), vec![6, 4]);
+/// assert_eq!(this.resample(0, 4, 2, ResampleMode::Mean), vec![3, 2]);
+/// assert_eq!(this.resample(0, 4, 2, ResampleMode::Max), vec![4, 2]);
+/// assert_eq!(this.resample(0, 4, 2, ResampleMode::Start), vec![4, 2]);
+/// ```
+pub enum ResampleMode {
+    /// Integral divided by the width of the bin. For integer values this division truncates.
+    Mean,
+    /// Sum of width × value of the parts of the pieces inside the bin.
+    Integral,
+    /// Highest value inside the bin.
+    Max,
+    /// Value at the start of the bin.
+    Start,
+}
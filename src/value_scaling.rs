@@ -0,0 +1,65 @@
+use crate::WidthMul;
+use core::cmp::PartialOrd;
+use num_traits::Num;
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+/// How the value of an interval changes when its bounds are scaled.
+///
+/// # Examples
+///
+/// ```
+/// use intervalues::{Interval, ValueScaling};
+///
+/// // 2 units per hour, from hour 1 to hour 3, expressed in minutes
+/// let rate = Interval::new(1, 3, 120);
+/// assert_eq!(rate.scale(60, ValueScaling::Density), Some(Interval::new(60, 180, 120)));
+/// assert_eq!(rate.scale(60, ValueScaling::Integral), Some(Interval::new(60, 180, 2)));
+/// assert_eq!(rate.scale(0, ValueScaling::Integral), None);
+/// ```
+pub enum ValueScaling {
+    /// Keep the value as it is, like a density or a rate. The total value (width × value) scales
+    /// along with the width.
+    Density,
+    /// Divide the value by the absolute scale factor, so the total value stays the same. For
+    /// integer values this division truncates. Scaling by zero is not possible this way.
+    Integral,
+}
+
+impl ValueScaling {
+    // The new value, or None when dividing by a factor of zero
+    pub(crate) fn apply<T, U>(self, value: U, factor: T) -> Option<U>
+    where
+        T: Num + PartialOrd + WidthMul<U, Output = U>,
+        U: Num,
+    {
+        match self {
+            ValueScaling::Density => Some(value),
+            ValueScaling::Integral if factor.is_zero() => None,
+            ValueScaling::Integral if factor < T::zero() => {
+                Some(value / (T::zero() - factor).width_mul(U::one()))
+            }
+            ValueScaling::Integral => Some(value / factor.width_mul(U::one())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(ValueScaling::Density.apply(6, 2), Some(6));
+        assert_eq!(ValueScaling::Integral.apply(6, 2), Some(3));
+        assert_eq!(ValueScaling::Integral.apply(6, -2), Some(3));
+        assert_eq!(ValueScaling::Integral.apply(3.0, 2.0f32), Some(1.5));
+        assert_eq!(ValueScaling::Integral.apply(3.0, 4i64), Some(0.75));
+    }
+
+    #[test]
+    fn test_apply_zero() {
+        assert_eq!(ValueScaling::Density.apply(6, 0), Some(6));
+        assert_eq!(ValueScaling::Integral.apply(6, 0), None);
+        assert_eq!(ValueScaling::Integral.apply(6.0, 0.0), None);
+    }
+}